@@ -1,69 +1,126 @@
-// Prevents additional console window on Windows in release, DO NOT REMOVE!!
-#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
-
-use axial_ledger::Ledger;
-use axial_router::{Router, Strategy};
-use axial_core::TaskNode;
-use std::path::PathBuf;
-
-#[tauri::command]
-async fn run_axial_task(task: String) -> Result<String, String> {
-    let ledger_path = if cfg!(windows) {
-        PathBuf::from(std::env::var("USERPROFILE").unwrap()).join(".axial").join("ledger.db")
-    } else {
-        PathBuf::from(std::env::var("HOME").unwrap()).join(".axial").join("ledger.db")
-    };
-
-    let mut ledger = Ledger::new(ledger_path.to_str().unwrap()).await
-        .map_err(|e| e.to_string())?;
-
-    ledger.append(serde_json::json!({
-        "event": "ui_task_request",
-        "task": task
-    })).await.map_err(|e| e.to_string())?;
-
-    let router = Router::new(Strategy::Privacy);
-    let node = TaskNode {
-        id: "ui-task".to_string(),
-        task_type: "nlp".to_string(),
-        params: serde_json::json!({ "instruction": task }),
-        invariants: vec![],
-        approval_gate: None,
-    };
-
-    let result = router.route(&node).map_err(|e| e.to_string())?;
-
-    Ok(format!("Routed to {} with score {}", result.adapter_id, result.score))
-}
-
-#[tauri::command]
-async fn get_ledger_entries() -> Result<Vec<serde_json::Value>, String> {
-    let ledger_path = if cfg!(windows) {
-        PathBuf::from(std::env::var("USERPROFILE").unwrap()).join(".axial").join("ledger.db")
-    } else {
-        PathBuf::from(std::env::var("HOME").unwrap()).join(".axial").join("ledger.db")
-    };
-
-    let ledger = Ledger::new(ledger_path.to_str().unwrap()).await
-        .map_err(|e| e.to_string())?;
-
-    let entries = ledger.query("SELECT payload FROM entries ORDER BY index DESC LIMIT 50")
-        .await.map_err(|e| e.to_string())?;
-
-    Ok(entries)
-}
-
-#[tauri::command]
-async fn get_pty_events(session_id: String) -> Result<Vec<axial_pty::PtyEvent>, String> {
-    // v1-max: In a real app, we'd fetch from the daemon or the manager
-    // For now, we mock returning events for the current session
-    Ok(vec![])
-}
-
-fn main() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_shell::init())
-        .invoke_handler(tauri::generate_handler![run_axial_task, get_ledger_entries, get_pty_events])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
+// Prevents additional console window on Windows in release, DO NOT REMOVE!!
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+
+use axial_ledger::Ledger;
+use axial_router::{Router, Strategy};
+use axial_core::TaskNode;
+use axial_shield::{KillScope, KillSwitch};
+use std::path::PathBuf;
+
+#[tauri::command]
+async fn run_axial_task(task: String) -> Result<String, String> {
+    let ledger_path = if cfg!(windows) {
+        PathBuf::from(std::env::var("USERPROFILE").unwrap()).join(".axial").join("ledger.db")
+    } else {
+        PathBuf::from(std::env::var("HOME").unwrap()).join(".axial").join("ledger.db")
+    };
+
+    let mut ledger = Ledger::new(ledger_path.to_str().unwrap()).await
+        .map_err(|e| e.to_string())?;
+
+    ledger.append(serde_json::json!({
+        "event": "ui_task_request",
+        "task": task
+    })).await.map_err(|e| e.to_string())?;
+
+    let mut router = Router::new();
+    let ollama_url = axial_router::adapters::ollama::DEFAULT_BASE_URL;
+    let models = axial_router::adapters::ollama::discover(ollama_url).await.map_err(|e| e.to_string())?;
+    for provider in models {
+        router.add_provider(Box::new(provider));
+    }
+    let strategy = Strategy::privacy_first();
+    let node = TaskNode {
+        id: "ui-task".to_string(),
+        task_type: "nlp".to_string(),
+        params: serde_json::json!({ "instruction": task }),
+        invariants: vec![],
+        approval_gate: None,
+    };
+
+    let decisions = router.route_node(&node, &strategy, None);
+    let result = decisions.first().ok_or_else(|| "No suitable provider found".to_string())?;
+
+    Ok(format!("Routed to {} with score {:.1}", result.provider_id, result.score))
+}
+
+#[tauri::command]
+async fn get_ledger_entries() -> Result<Vec<serde_json::Value>, String> {
+    let ledger_path = if cfg!(windows) {
+        PathBuf::from(std::env::var("USERPROFILE").unwrap()).join(".axial").join("ledger.db")
+    } else {
+        PathBuf::from(std::env::var("HOME").unwrap()).join(".axial").join("ledger.db")
+    };
+
+    let ledger = Ledger::new(ledger_path.to_str().unwrap()).await
+        .map_err(|e| e.to_string())?;
+
+    let entries = ledger.query("SELECT payload FROM entries ORDER BY index DESC LIMIT 50")
+        .await.map_err(|e| e.to_string())?;
+
+    Ok(entries)
+}
+
+#[tauri::command]
+async fn get_pty_events(session_id: String) -> Result<Vec<axial_pty::PtyEvent>, String> {
+    // v1-max: In a real app, we'd fetch from the daemon or the manager
+    // For now, we mock returning events for the current session
+    Ok(vec![])
+}
+
+fn axial_dir() -> PathBuf {
+    let home = if cfg!(windows) { std::env::var("USERPROFILE") } else { std::env::var("HOME") };
+    PathBuf::from(home.unwrap()).join(".axial")
+}
+
+/// Engages or re-arms the shared kill switch file and records it in the ledger. The daemon
+/// notices the change and stops affected sessions.
+async fn record_kill_switch(record: axial_shield::KillRecord) -> Result<axial_shield::KillRecord, String> {
+    let ledger_path = axial_dir().join("ledger.db");
+    let mut ledger = Ledger::new(ledger_path.to_str().unwrap()).await
+        .map_err(|e| e.to_string())?;
+    ledger.append(record.to_ledger_payload()).await.map_err(|e| e.to_string())?;
+    Ok(record)
+}
+
+fn open_kill_switch() -> Result<KillSwitch, String> {
+    KillSwitch::open(&axial_dir().join("shield").join(axial_shield::killswitch::KILL_SWITCH_FILE))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn shield_kill(provider: Option<String>, run: Option<String>, reason: Option<String>) -> Result<axial_shield::KillRecord, String> {
+    let scope = KillScope::from_parts(provider, run).map_err(|e| e.to_string())?;
+    let record = open_kill_switch()?.engage(scope, reason, "ui").map_err(|e| e.to_string())?;
+    record_kill_switch(record).await
+}
+
+#[tauri::command]
+async fn shield_rearm(provider: Option<String>, run: Option<String>, reason: String) -> Result<axial_shield::KillRecord, String> {
+    if reason.trim().is_empty() {
+        return Err("Re-arming the kill switch requires a reason".to_string());
+    }
+    let scope = KillScope::from_parts(provider, run).map_err(|e| e.to_string())?;
+    let record = open_kill_switch()?.rearm(&scope, Some(reason), "ui").map_err(|e| e.to_string())?;
+    record_kill_switch(record).await
+}
+
+#[tauri::command]
+fn shield_kill_status() -> Result<Vec<axial_shield::KillEntry>, String> {
+    Ok(open_kill_switch()?.engaged())
+}
+
+fn main() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_shell::init())
+        .invoke_handler(tauri::generate_handler![
+            run_axial_task,
+            get_ledger_entries,
+            get_pty_events,
+            shield_kill,
+            shield_rearm,
+            shield_kill_status
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
@@ -1,5 +1,5 @@
-pub mod cursor;
-pub mod codex;
-pub mod claude;
-pub mod aider;
-pub mod cline;
+pub mod cursor;
+pub mod codex;
+pub mod claude;
+pub mod aider;
+pub mod cline;
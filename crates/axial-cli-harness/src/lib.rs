@@ -1,51 +1,153 @@
-pub mod adapters;
-
-use async_trait::async_trait;
-use serde::{Serialize, Deserialize};
-use anyhow::Result;
-use std::process::Command;
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ToolStatus {
-    pub name: String,
-    pub installed: bool,
-    pub version: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ToolResult {
-    pub stdout: String,
-    pub stderr: String,
-    pub diff: Option<String>,
-}
-
-#[async_trait]
-pub trait ToolAdapter: Send + Sync {
-    fn name(&self) -> &str;
-    async fn probe(&self) -> ToolStatus;
-    async fn run(&self, task: &str, dry_run: bool) -> Result<ToolResult>;
-}
-
-pub struct Harness {
-    adapters: Vec<Box<dyn ToolAdapter>>,
-}
-
-impl Harness {
-    pub fn new() -> Self {
-        Self {
-            adapters: Vec::new(),
-        }
-    }
-
-    pub fn add_adapter(&mut self, adapter: Box<dyn ToolAdapter>) {
-        self.adapters.push(adapter);
-    }
-
-    pub async fn probe_all(&self) -> Vec<ToolStatus> {
-        let mut results = Vec::new();
-        for adapter in &self.adapters {
-            results.push(adapter.probe().await);
-        }
-        results
-    }
-}
+pub mod adapters;
+pub mod guard;
+
+use async_trait::async_trait;
+use serde::{Serialize, Deserialize};
+use anyhow::Result;
+use axial_shield::WorkspaceGuard;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ToolStatus {
+    pub name: String,
+    pub installed: bool,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ToolResult {
+    pub stdout: String,
+    pub stderr: String,
+    pub diff: Option<String>,
+}
+
+#[async_trait]
+pub trait ToolAdapter: Send + Sync {
+    fn name(&self) -> &str;
+    async fn probe(&self) -> ToolStatus;
+    /// Runs the tool in `workspace`'s root. Everything it writes is checked against
+    /// `workspace` when it exits, see `run_guarded`.
+    async fn run(&self, task: &str, dry_run: bool, workspace: &WorkspaceGuard) -> Result<ToolResult>;
+}
+
+/// Writes a file on an adapter's behalf, refusing paths outside the workspace or inside a
+/// read-only zone. Returns the path actually written.
+pub fn write_file(workspace: &WorkspaceGuard, path: &Path, contents: &[u8]) -> Result<PathBuf> {
+    let resolved = workspace.check_write(path)?;
+    if let Some(parent) = resolved.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&resolved, contents)?;
+    Ok(resolved)
+}
+
+/// Checks every file a unified diff creates, changes or deletes against `workspace`. Paths
+/// are expected with git's default `a/` and `b/` prefixes.
+pub fn check_diff(workspace: &WorkspaceGuard, diff: &str) -> Result<()> {
+    for line in diff.lines() {
+        let Some(path) = line.strip_prefix("+++ ").or_else(|| line.strip_prefix("--- ")) else { continue };
+        let path = path.split('\t').next().unwrap_or(path).trim();
+        if path == "/dev/null" {
+            continue;
+        }
+        let path = path.strip_prefix("a/").or_else(|| path.strip_prefix("b/")).unwrap_or(path);
+        workspace.check_write(Path::new(path))?;
+    }
+    Ok(())
+}
+
+/// What a tool changed in tracked files under the workspace root, as a `git diff` checked
+/// against `workspace`. `None` when nothing changed or the root isn't a git checkout.
+pub fn workspace_diff(workspace: &WorkspaceGuard) -> Result<Option<String>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(workspace.root())
+        // Fixed prefixes, whatever `diff.noprefix` or `diff.mnemonicPrefix` say: `check_diff`
+        // strips `a/` and `b/`.
+        .args(["diff", "--no-color", "--no-ext-diff", "--relative", "--src-prefix=a/", "--dst-prefix=b/"])
+        .output();
+    let diff = match output {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).into_owned(),
+        _ => return Ok(None),
+    };
+    check_diff(workspace, &diff)?;
+    Ok((!diff.is_empty()).then_some(diff))
+}
+
+/// Runs an adapter's command in the workspace root under a `guard::WriteAudit`: once it
+/// exits, the read-only zones and the symlinks under the roots are checked against
+/// `workspace` and writes it wasn't allowed are undone. Returns its output and the
+/// `workspace_diff`.
+pub fn run_guarded(tool: &str, workspace: &WorkspaceGuard, cmd: &mut Command) -> Result<(Output, Option<String>)> {
+    let audit = guard::WriteAudit::capture(workspace)?;
+    let output = run_tracked(tool, cmd.current_dir(workspace.root()));
+    // Checked even when the tool failed: it may have written before it did.
+    audit.verify(workspace)?;
+    Ok((output?, workspace_diff(workspace)?))
+}
+
+/// Processes started by adapters that are still running, by pid, with the tool's name.
+fn running() -> &'static Mutex<HashMap<u32, String>> {
+    static RUNNING: OnceLock<Mutex<HashMap<u32, String>>> = OnceLock::new();
+    RUNNING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Runs `cmd` to completion like `Command::output`, registered so `terminate_all` can stop it.
+pub fn run_tracked(tool: &str, cmd: &mut Command) -> std::io::Result<Output> {
+    let child = cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let pid = child.id();
+    running().lock().unwrap().insert(pid, tool.to_string());
+    let output = child.wait_with_output();
+    running().lock().unwrap().remove(&pid);
+    output
+}
+
+/// Kills every adapter process this process started, returning the names of the tools stopped.
+pub fn terminate_all() -> Vec<String> {
+    let running = running().lock().unwrap();
+    running.iter()
+        .filter(|(pid, _)| kill(**pid))
+        .map(|(_, tool)| tool.clone())
+        .collect()
+}
+
+#[cfg(unix)]
+fn kill(pid: u32) -> bool {
+    use nix::sys::signal::{self, Signal};
+    signal::kill(nix::unistd::Pid::from_raw(pid as i32), Signal::SIGKILL).is_ok()
+}
+
+#[cfg(not(unix))]
+fn kill(pid: u32) -> bool {
+    Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T", "/F"])
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+pub struct Harness {
+    adapters: Vec<Box<dyn ToolAdapter>>,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self {
+            adapters: Vec::new(),
+        }
+    }
+
+    pub fn add_adapter(&mut self, adapter: Box<dyn ToolAdapter>) {
+        self.adapters.push(adapter);
+    }
+
+    pub async fn probe_all(&self) -> Vec<ToolStatus> {
+        let mut results = Vec::new();
+        for adapter in &self.adapters {
+            results.push(adapter.probe().await);
+        }
+        results
+    }
+}
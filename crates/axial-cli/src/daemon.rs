@@ -1,219 +1,889 @@
-use axum::{
-    routing::{get, post},
-    Json, Router, extract::{State, Path},
-};
-use std::sync::Arc;
-use tokio::sync::{Mutex, broadcast};
-use anyhow::Result;
-use serde::{Serialize, Deserialize};
-use axial_pty::PtyManager;
-use axial_ledger::Ledger;
-use axial_core::{PlanPacket, TaskNode};
-use std::path::PathBuf;
-use tower_http::cors::CorsLayer;
-
-pub struct AppState {
-    pub pty_manager: Mutex<PtyManager>,
-    pub ledger: Mutex<Ledger>,
-    pub event_tx: broadcast::Sender<EventPacket>,
-    pub gate_responses: Mutex<std::collections::HashMap<String, bool>>,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct EventPacket {
-    pub id: String,
-    pub timestamp: chrono::DateTime<chrono::Utc>,
-    pub payload: serde_json::Value,
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct DaemonStatus {
-    pub status: String,
-    pub sessions_count: usize,
-}
-
-#[derive(Deserialize)]
-pub struct SpawnRequest {
-    pub command: String,
-}
-
-#[derive(Serialize)]
-pub struct SpawnResponse {
-    pub session_id: String,
-}
-
-pub async fn start_daemon(port: u16, ledger_path: PathBuf) -> Result<()> {
-    let pty_manager = PtyManager::new();
-    let ledger = Ledger::new(ledger_path.to_str().unwrap()).await?;
-    let (event_tx, _) = broadcast::channel(100);
-    
-    let state = Arc::new(AppState {
-        pty_manager: Mutex::new(pty_manager),
-        ledger: Mutex::new(ledger),
-        event_tx,
-        gate_responses: Mutex::new(std::collections::HashMap::new()),
-    });
-
-    let app = Router::new()
-        .route("/status", get(get_status))
-        .route("/plan", post(handle_plan))
-        .route("/run", post(handle_run))
-        .route("/approve", post(handle_approve))
-        .route("/pty/spawn", post(spawn_pty))
-        .route("/pty/replay/:id", get(replay_pty))
-        .route("/ledger/query", post(query_ledger))
-        .route("/ledger/semantic-search", post(semantic_search))
-        .layer(CorsLayer::permissive())
-        .with_state(state);
-
-    let addr = format!("127.0.0.1:{}", port);
-    println!("AXIAL Daemon (Claude Code Orchestrator) listening on http://{}", addr);
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
-
-    Ok(())
-}
-
-#[derive(Deserialize)]
-struct ApprovalRequest {
-    pub gate_id: String,
-    pub approved: bool,
-}
-
-async fn handle_plan(
-    State(state): State<Arc<AppState>>,
-    Json(plan): Json<PlanPacket>,
-) -> Json<serde_json::Value> {
-    let mut ledger = state.ledger.lock().await;
-    let entry = ledger.append(serde_json::json!({
-        "event": "plan_received",
-        "plan_id": plan.id,
-        "title": plan.title
-    })).await.unwrap();
-
-    Json(serde_json::json!({ "status": "stored", "ledger_index": entry.index }))
-}
-
-async fn handle_run(
-    State(state): State<Arc<AppState>>,
-    Json(payload): Json<serde_json::Value>,
-) -> Json<serde_json::Value> {
-    let plan_id = payload.get("plan_id").and_then(|v| v.as_str()).unwrap_or("none");
-    println!("AXIAL: Starting execution for plan {}", plan_id);
-    
-    // v1-max Orchestrator: Spawn a dedicated task runner
-    let state_clone = Arc::clone(&state);
-    let plan_id_str = plan_id.to_string();
-    
-    tokio::spawn(async move {
-        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
-        
-        // 1. Audit start & Snapshot
-        {
-            let mut ledger = state_clone.ledger.lock().await;
-            let _ = ledger.snapshot(&format!("pre-exec-{}", plan_id_str)).await;
-            let _ = ledger.append(serde_json::json!({
-                "event": "execution_started",
-                "plan_id": plan_id_str
-            })).await;
-        }
-
-        // 2. Fetch Plan (In a real impl, we'd store the plan in the DB/Ledger first)
-        // Here we assume it's valid and we begin execution.
-        
-        // Mocking execution of nodes for v1-max demo
-        println!("AXIAL [Plan {}]: Running Invariants...", plan_id_str);
-        
-        // Check Truth Engines (Shield)
-        let semgrep_results = axial_shield::TruthEngine::run_semgrep(".").unwrap_or_default();
-        if !semgrep_results.is_empty() {
-            println!("AXIAL [Plan {}]: Violations found! HALTING.", plan_id_str);
-            let mut ledger = state_clone.ledger.lock().await;
-            let _ = ledger.append(serde_json::json!({
-                "event": "execution_halted",
-                "reason": "shield_violation",
-                "details": semgrep_results
-            })).await;
-            return;
-        }
-
-        println!("AXIAL [Plan {}]: Execution Complete.", plan_id_str);
-    });
-
-    Json(serde_json::json!({ "status": "started", "plan_id": plan_id }))
-}
-
-async fn handle_approve(
-    State(state): State<Arc<AppState>>,
-    Json(payload): Json<ApprovalRequest>,
-) -> Json<serde_json::Value> {
-    let mut responses = state.gate_responses.lock().await;
-    responses.insert(payload.gate_id.clone(), payload.approved);
-    
-    let mut ledger = state.ledger.lock().await;
-    ledger.append(serde_json::json!({
-        "event": "approval_gate_response",
-        "gate_id": payload.gate_id,
-        "approved": payload.approved
-    })).await.unwrap();
-
-    Json(serde_json::json!({ "status": "acknowledged" }))
-}
-
-#[derive(Deserialize)]
-struct LedgerQuery {
-    query: String,
-}
-
-#[derive(Deserialize)]
-struct SemanticSearchRequest {
-    text: String,
-    limit: usize,
-}
-
-async fn query_ledger(
-    State(state): State<Arc<AppState>>,
-    Json(payload): Json<LedgerQuery>,
-) -> Json<Vec<axial_core::schemas::LedgerEntry>> {
-    let ledger = state.ledger.lock().await;
-    let res = ledger.query(&payload.query).await.unwrap_or_default();
-    Json(res)
-}
-
-async fn semantic_search(
-    State(state): State<Arc<AppState>>,
-    Json(payload): Json<SemanticSearchRequest>,
-) -> Json<Vec<axial_core::schemas::LedgerEntry>> {
-    let ledger = state.ledger.lock().await;
-    let res = ledger.search_semantic(&payload.text, payload.limit).await.unwrap_or_default();
-    Json(res)
-}
-
-async fn get_status(State(state): State<Arc<AppState>>) -> Json<DaemonStatus> {
-    // Note: This is an approximation for v1-max
-    Json(DaemonStatus {
-        status: "Running".to_string(),
-        sessions_count: 0, 
-    })
-}
-
-async fn spawn_pty(
-    State(state): State<Arc<AppState>>,
-    Json(payload): Json<SpawnRequest>,
-) -> Json<SpawnResponse> {
-    let mut manager = state.pty_manager.lock().await;
-    let session = manager.spawn(&payload.command).unwrap(); // Handle error in v1-max properly
-    
-    Json(SpawnResponse {
-        session_id: session.id,
-    })
-}
-
-async fn replay_pty(
-    State(state): State<Arc<AppState>>,
-    Path(id): Path<String>,
-) -> Json<Vec<axial_pty::PtyEvent>> {
-    let mut manager = state.pty_manager.lock().await;
-    let events = manager.replay(&id, None).unwrap_or_default();
-    Json(events)
-}
+use axum::{
+    routing::{get, post},
+    Json, Router, extract::{State, Path},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::{Stream, StreamExt};
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::{Mutex, broadcast};
+use anyhow::Result;
+use serde::{Serialize, Deserialize};
+use axial_pty::PtyManager;
+use axial_ledger::Ledger;
+use axial_core::{PlanPacket, TaskNode};
+use std::path::PathBuf;
+use tower_http::cors::CorsLayer;
+
+pub struct AppState {
+    pub pty_manager: Mutex<PtyManager>,
+    pub ledger: Mutex<Ledger>,
+    pub event_tx: broadcast::Sender<EventPacket>,
+    pub gate_responses: Mutex<std::collections::HashMap<String, bool>>,
+    pub router: axial_router::Router,
+    /// Spend so far, kept current as outcomes are recorded so budgets don't reread the ledger.
+    pub spend: Mutex<axial_router::budget::SpendReport>,
+    pub shield: Arc<axial_shield::Shield>,
+    /// Policy file behind `shield`, watched for changes; `None` for the built-in default.
+    pub shield_policy: Option<PathBuf>,
+    /// Each run's vault, opened once so concurrent requests add to it instead of each saving
+    /// its own copy over the others'.
+    pub vaults: Mutex<std::collections::HashMap<String, Arc<Mutex<axial_shield::Vault>>>>,
+}
+
+/// How often the shield policy file is checked for changes.
+const SHIELD_POLICY_POLL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// How often the kill switch file is checked for scopes engaged by other processes.
+const KILL_SWITCH_POLL: std::time::Duration = std::time::Duration::from_secs(1);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EventPacket {
+    pub id: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub status: String,
+    pub sessions_count: usize,
+    pub breakers: std::collections::HashMap<String, axial_router::breaker::BreakerSnapshot>,
+    #[serde(default)]
+    pub health: std::collections::HashMap<String, axial_router::health::ProviderHealth>,
+    #[serde(default)]
+    pub rate_limits: std::collections::HashMap<String, axial_router::ratelimit::RateLimitSnapshot>,
+    #[serde(default)]
+    pub kill_switch: Vec<axial_shield::KillEntry>,
+}
+
+#[derive(Deserialize)]
+pub struct SpawnRequest {
+    pub command: String,
+    /// Run the session belongs to, so a run-scoped kill switch stops it.
+    #[serde(default)]
+    pub run_id: Option<String>,
+}
+
+/// Body of `POST /shield/kill` and `POST /shield/rearm`. Neither `provider` nor `run_id`
+/// means the global switch.
+#[derive(Deserialize)]
+pub struct KillRequest {
+    pub provider: Option<String>,
+    pub run_id: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SpawnResponse {
+    pub session_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct ExecuteRequest {
+    pub task: String,
+    /// Capabilities required on top of those derived from `task_type` and `params`.
+    #[serde(default)]
+    pub requirements: Vec<String>,
+    pub strategy: Option<String>,
+    pub task_type: Option<String>,
+    #[serde(default)]
+    pub params: serde_json::Value,
+    pub run_id: Option<String>,
+    pub plan_id: Option<String>,
+    /// Profile whose budgets, routing constraints and privacy policy apply to this call.
+    pub profile: Option<String>,
+    /// Per-run spend limit in USD, on top of any profile budgets.
+    pub max_cost_usd: Option<f64>,
+}
+
+pub async fn start_daemon(port: u16, ledger_path: PathBuf, profile: Option<axial_core::Profile>) -> Result<()> {
+    let pty_manager = PtyManager::new();
+    let ledger = Ledger::new(ledger_path.to_str().unwrap()).await?;
+    let mut router = crate::build_router().await;
+    if let Some(cache) = crate::open_cache(&ledger_path).await {
+        router.set_cache(cache);
+    }
+    let records = crate::timestamped_outcomes(&ledger).await;
+    router.load_outcomes(&records.iter().map(|(_, o)| o.clone()).collect::<Vec<_>>());
+    let spend = axial_router::budget::SpendReport::from_outcomes(&records, chrono::Utc::now());
+    let shield_policy = crate::shield_policy_path(profile.as_ref())?;
+    let shield = Arc::new(crate::build_shield(profile.as_ref())?);
+    let (event_tx, _) = broadcast::channel(1024);
+    
+    let state = Arc::new(AppState {
+        pty_manager: Mutex::new(pty_manager),
+        ledger: Mutex::new(ledger),
+        event_tx,
+        gate_responses: Mutex::new(std::collections::HashMap::new()),
+        router,
+        spend: Mutex::new(spend),
+        shield,
+        shield_policy: shield_policy.clone(),
+        vaults: Mutex::new(std::collections::HashMap::new()),
+    });
+
+    // Watch the selected policy, or the default location so a policy created later is picked up.
+    let watched = match shield_policy {
+        Some(path) => path,
+        None => crate::config_dir()?.join("shield").join("policy.json"),
+    };
+    tokio::spawn(watch_shield_policy(Arc::clone(&state), watched));
+    tokio::spawn(watch_kill_switch(Arc::clone(&state)));
+
+    // Keep probing providers so unreachable ones drop out of routing and come back on recovery.
+    let interval_secs = std::env::var("AXIAL_HEALTH_INTERVAL_SECS").ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(axial_router::health::DEFAULT_PROBE_INTERVAL_SECS);
+    let probe_state = Arc::clone(&state);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs.max(1)));
+        let mut seq = 0u64;
+        loop {
+            interval.tick().await;
+            let health = probe_state.router.probe_health().await;
+            publish_event(&probe_state, format!("health:{}", seq), serde_json::json!({
+                "event": "provider_health",
+                "providers": health,
+            }));
+            seq += 1;
+        }
+    });
+
+    let app = Router::new()
+        .route("/status", get(get_status))
+        .route("/plan", post(handle_plan))
+        .route("/run", post(handle_run))
+        .route("/approve", post(handle_approve))
+        .route("/execute", post(handle_execute))
+        .route("/events", get(stream_events))
+        .route("/pty/spawn", post(spawn_pty))
+        .route("/pty/replay/:id", get(replay_pty))
+        .route("/ledger/query", post(query_ledger))
+        .route("/ledger/semantic-search", post(semantic_search))
+        .route("/shield/kill", get(get_kill_switch).post(handle_kill))
+        .route("/shield/rearm", post(handle_rearm))
+        .layer(CorsLayer::permissive())
+        .with_state(state);
+
+    let addr = format!("127.0.0.1:{}", port);
+    println!("AXIAL Daemon (Claude Code Orchestrator) listening on http://{}", addr);
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+/// Polls the shield policy file and swaps it into the live shield when it changes. A policy
+/// that fails to load is recorded and the previous one stays in force.
+async fn watch_shield_policy(state: Arc<AppState>, path: PathBuf) {
+    let modified = |path: &PathBuf| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let mut last = modified(&path);
+    let mut interval = tokio::time::interval(SHIELD_POLICY_POLL);
+    let mut seq = 0u64;
+    loop {
+        interval.tick().await;
+        let current = modified(&path);
+        if current.is_none() || current == last {
+            continue;
+        }
+        last = current;
+
+        let payload = match axial_shield::ShieldConfig::load(&path).and_then(|config| state.shield.reload(config)) {
+            Ok(()) => serde_json::json!({ "event": "shield_policy_reloaded", "path": path }),
+            Err(e) => serde_json::json!({ "event": "shield_policy_rejected", "path": path, "error": e.to_string() }),
+        };
+        {
+            let mut ledger = state.ledger.lock().await;
+            let _ = ledger.append(payload.clone()).await;
+        }
+        publish_event(&state, format!("shield-policy:{}", seq), payload);
+        seq += 1;
+    }
+}
+
+/// Stops the PTY sessions the engaged kill switch scopes cover: all of them for a global
+/// kill, the run's sessions for a run kill. Provider kills are enforced per stream in
+/// `handle_execute`. Agent tools run by `axial tool run` live in that CLI process, whose
+/// own watchdog stops them. Returns the ids of the sessions stopped.
+async fn enforce_kill_switch(state: &AppState) -> Vec<String> {
+    let engaged = state.shield.kill_switch().engaged();
+    if engaged.is_empty() {
+        return Vec::new();
+    }
+    let manager = state.pty_manager.lock().await;
+    manager.terminate(|session| engaged.iter().any(|e| e.applies_to(None, session.run_id.as_deref())))
+}
+
+/// Picks up scopes engaged or re-armed by the CLI or the Command Center and enforces them.
+/// The process that changed the switch already wrote the ledger record.
+async fn watch_kill_switch(state: Arc<AppState>) {
+    let scopes = |state: &AppState| -> Vec<axial_shield::KillScope> {
+        state.shield.kill_switch().engaged().into_iter().map(|e| e.scope).collect()
+    };
+    let mut last = scopes(&state);
+    let mut interval = tokio::time::interval(KILL_SWITCH_POLL);
+    let mut seq = 0u64;
+    loop {
+        interval.tick().await;
+        let current = scopes(&state);
+        if current == last {
+            continue;
+        }
+        last = current.clone();
+        let stopped = enforce_kill_switch(&state).await;
+        publish_event(&state, format!("kill-switch-watch:{}", seq), serde_json::json!({
+            "event": "shield_kill_switch_changed",
+            "engaged": current,
+            "stopped": stopped,
+        }));
+        seq += 1;
+    }
+}
+
+async fn get_kill_switch(State(state): State<Arc<AppState>>) -> Json<Vec<axial_shield::KillEntry>> {
+    Json(state.shield.kill_switch().engaged())
+}
+
+async fn handle_kill(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<KillRequest>,
+) -> Json<serde_json::Value> {
+    let record = match axial_shield::KillScope::from_parts(payload.provider, payload.run_id)
+        .and_then(|scope| state.shield.kill_switch().engage(scope, payload.reason, "daemon"))
+    {
+        Ok(record) => record,
+        Err(e) => return Json(serde_json::json!({ "status": "error", "error": e.to_string() })),
+    };
+    {
+        let mut ledger = state.ledger.lock().await;
+        let _ = ledger.append(record.to_ledger_payload()).await;
+    }
+    let stopped = enforce_kill_switch(&state).await;
+    let mut event = record.to_ledger_payload();
+    event["stopped"] = serde_json::json!(stopped);
+    publish_event(&state, format!("kill-switch:{}", uuid::Uuid::new_v4()), event);
+    Json(serde_json::json!({ "status": "engaged", "scope": record.scope, "stopped": stopped }))
+}
+
+async fn handle_rearm(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<KillRequest>,
+) -> Json<serde_json::Value> {
+    if payload.reason.as_deref().is_none_or(|r| r.trim().is_empty()) {
+        return Json(serde_json::json!({ "status": "error", "error": "Re-arming the kill switch requires a reason" }));
+    }
+    let record = match axial_shield::KillScope::from_parts(payload.provider, payload.run_id)
+        .and_then(|scope| state.shield.kill_switch().rearm(&scope, payload.reason, "daemon"))
+    {
+        Ok(record) => record,
+        Err(e) => return Json(serde_json::json!({ "status": "error", "error": e.to_string() })),
+    };
+    {
+        let mut ledger = state.ledger.lock().await;
+        let _ = ledger.append(record.to_ledger_payload()).await;
+    }
+    publish_event(&state, format!("kill-switch:{}", uuid::Uuid::new_v4()), record.to_ledger_payload());
+    Json(serde_json::json!({ "status": "rearmed", "scope": record.scope }))
+}
+
+#[derive(Deserialize)]
+struct ApprovalRequest {
+    pub gate_id: String,
+    pub approved: bool,
+}
+
+async fn handle_plan(
+    State(state): State<Arc<AppState>>,
+    Json(plan): Json<PlanPacket>,
+) -> Json<serde_json::Value> {
+    let mut ledger = state.ledger.lock().await;
+    let entry = ledger.append(serde_json::json!({
+        "event": "plan_received",
+        "plan_id": plan.id,
+        "title": plan.title
+    })).await.unwrap();
+
+    Json(serde_json::json!({ "status": "stored", "ledger_index": entry.index }))
+}
+
+/// The profile's budgets plus an optional per-run cap, and what is left of them.
+async fn budget_headroom(
+    state: &AppState,
+    profile: Option<&axial_core::Profile>,
+    max_cost_usd: Option<f64>,
+    run_id: Option<&str>,
+    plan_id: Option<&str>,
+) -> (axial_router::budget::BudgetPolicy, axial_router::budget::Headroom) {
+    let mut policy = match profile {
+        Some(profile) => axial_router::budget::BudgetPolicy::from_profile(profile),
+        None => axial_router::budget::BudgetPolicy::default(),
+    };
+    if let Some(usd) = max_cost_usd {
+        policy = policy.with(axial_router::budget::Budget {
+            scope: axial_router::budget::BudgetScope::Run,
+            max_cost_usd: Some(usd),
+            max_tokens: None,
+        });
+    }
+    let mut spend = state.spend.lock().await;
+    spend.roll_over(chrono::Utc::now());
+    let headroom = policy.headroom(&spend, profile.map(|p| p.name.as_str()), run_id, plan_id);
+    (policy, headroom)
+}
+
+async fn handle_run(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    let plan_id = payload.get("plan_id").and_then(|v| v.as_str()).unwrap_or("none");
+    let run_id = payload.get("run_id").and_then(|v| v.as_str());
+    let profile = match payload.get("profile").and_then(|v| v.as_str()).map(crate::load_profile).transpose() {
+        Ok(profile) => profile,
+        Err(e) => return Json(serde_json::json!({ "status": "error", "error": e.to_string() })),
+    };
+    let max_cost_usd = payload.get("max_cost_usd").and_then(|v| v.as_f64());
+    // A run whose budget is already spent would be refused at its first provider call.
+    let (_, headroom) = budget_headroom(&state, profile.as_ref(), max_cost_usd, run_id, Some(plan_id)).await;
+    if headroom.is_exhausted() {
+        let reason = format!("Budget for plan {} is exhausted", plan_id);
+        let mut ledger = state.ledger.lock().await;
+        let _ = ledger.append(serde_json::json!({
+            "event": "budget_refused",
+            "run_id": run_id,
+            "plan_id": plan_id,
+            "reason": reason
+        })).await;
+        return Json(serde_json::json!({ "status": "refused", "error": reason }));
+    }
+    println!("AXIAL: Starting execution for plan {}", plan_id);
+    
+    // v1-max Orchestrator: Spawn a dedicated task runner
+    let state_clone = Arc::clone(&state);
+    let plan_id_str = plan_id.to_string();
+    
+    tokio::spawn(async move {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        
+        // 1. Audit start & Snapshot
+        // Truth engines later scan only what changed since this commit.
+        let scan = axial_shield::DiffScan::snapshot(std::path::Path::new("."));
+        {
+            let mut ledger = state_clone.ledger.lock().await;
+            let _ = ledger.snapshot(&format!("pre-exec-{}", plan_id_str)).await;
+            let _ = ledger.append(serde_json::json!({
+                "event": "execution_started",
+                "plan_id": plan_id_str,
+                "base": scan.as_ref().ok().map(|s| s.base()),
+            })).await;
+        }
+
+        // 2. Fetch Plan (In a real impl, we'd store the plan in the DB/Ledger first)
+        // Here we assume it's valid and we begin execution.
+        
+        // Mocking execution of nodes for v1-max demo
+        println!("AXIAL [Plan {}]: Running Invariants...", plan_id_str);
+        
+        // Check Truth Engines (Shield). Findings that predate the run don't halt it. The
+        // built-in secret scanner needs no external tool, so the gate holds on any machine.
+        // Without a baseline (not a git checkout, no commits) every finding in the tree counts.
+        let halt_severity = state_clone.shield.config().halt_severity;
+        let engines: [&dyn axial_shield::TruthEngine; 2] = [&axial_shield::SecretScanner, &axial_shield::truth::Semgrep];
+        let mut blocking = Vec::new();
+        let mut scan_failed = false;
+        let mut scanned = false;
+        for engine in engines {
+            let report = match &scan {
+                Ok(scan) => scan.run(engine),
+                Err(_) => engine.run(".").map(|new| axial_shield::DiffReport {
+                    engine: engine.name().to_string(),
+                    new,
+                    ..Default::default()
+                }),
+            };
+            let mut ledger = state_clone.ledger.lock().await;
+            match report {
+                Ok(report) => {
+                    scanned = true;
+                    blocking.extend(report.blocking(halt_severity).into_iter().cloned());
+                    let mut payload = report.to_ledger_payload(halt_severity);
+                    payload["plan_id"] = serde_json::json!(plan_id_str);
+                    let _ = ledger.append(payload).await;
+                }
+                Err(e) => {
+                    scan_failed = true;
+                    let _ = ledger.append(serde_json::json!({
+                        "event": "truth_scan_failed",
+                        "plan_id": plan_id_str,
+                        "engine": engine.name(),
+                        "error": e.to_string()
+                    })).await;
+                }
+            }
+        }
+        // The verdict feeds back into routing for the providers that served this plan. A scan
+        // that couldn't run says nothing about their work.
+        if !blocking.is_empty() || !scan_failed {
+            let verdict = axial_router::learning::InvariantVerdict { plan_id: plan_id_str.clone(), passed: blocking.is_empty() };
+            let mut ledger = state_clone.ledger.lock().await;
+            let _ = ledger.append(verdict.to_ledger_payload()).await;
+            state_clone.router.load_outcomes(&crate::provider_outcomes(&ledger).await);
+        }
+        if !blocking.is_empty() {
+            println!("AXIAL [Plan {}]: {} new violation(s) found! HALTING.", plan_id_str, blocking.len());
+            let mut ledger = state_clone.ledger.lock().await;
+            let _ = ledger.append(serde_json::json!({
+                "event": "execution_halted",
+                "reason": "shield_violation",
+                "details": blocking
+            })).await;
+            return;
+        }
+        if !scanned {
+            // No engine could check the run, so nothing vouches for it.
+            println!("AXIAL [Plan {}]: No truth engine could scan the changes! HALTING.", plan_id_str);
+            let mut ledger = state_clone.ledger.lock().await;
+            let _ = ledger.append(serde_json::json!({
+                "event": "execution_halted",
+                "plan_id": plan_id_str,
+                "reason": "truth_scan_failed"
+            })).await;
+            return;
+        }
+
+        println!("AXIAL [Plan {}]: Execution Complete.", plan_id_str);
+    });
+
+    Json(serde_json::json!({ "status": "started", "plan_id": plan_id }))
+}
+
+/// The vault for `scope`, opened from disk the first time a request in that run needs it.
+async fn run_vault(state: &AppState, scope: &str) -> Result<Arc<Mutex<axial_shield::Vault>>> {
+    let mut vaults = state.vaults.lock().await;
+    if let Some(vault) = vaults.get(scope) {
+        return Ok(Arc::clone(vault));
+    }
+    let vault = Arc::new(Mutex::new(crate::open_vault(scope)?));
+    vaults.insert(scope.to_string(), Arc::clone(&vault));
+    Ok(vault)
+}
+
+/// Routes a task and streams the provider's output onto `event_tx` as it arrives.
+async fn handle_execute(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<ExecuteRequest>,
+) -> Json<serde_json::Value> {
+    let ExecuteRequest { task, requirements, strategy, task_type, params, run_id, plan_id, profile, max_cost_usd } = payload;
+    let task_type = task_type.unwrap_or_else(|| "adhoc".to_string());
+    if let Some(entry) = state.shield.kill_switch().check(None, run_id.as_deref()) {
+        return Json(serde_json::json!({ "status": "killed", "scope": entry.scope, "reason": entry.reason }));
+    }
+    let strategy: axial_router::Strategy = match strategy.as_deref().unwrap_or("performance").parse() {
+        Ok(strategy) => strategy,
+        Err(e) => return Json(serde_json::json!({ "status": "error", "error": e.to_string() })),
+    };
+    let profile = match profile.as_deref().map(crate::load_profile).transpose() {
+        Ok(profile) => profile,
+        Err(e) => return Json(serde_json::json!({ "status": "error", "error": e.to_string() })),
+    };
+    let profile_name = profile.as_ref().map(|p| p.name.clone());
+    // A profile that selects a different policy than the daemon's gets its own shield.
+    let shield = match profile.as_ref().map(|p| crate::shield_policy_path(Some(p))).transpose() {
+        Ok(Some(path)) if path != state.shield_policy => match crate::build_shield(profile.as_ref()) {
+            Ok(shield) => Arc::new(shield),
+            Err(e) => return Json(serde_json::json!({ "status": "error", "error": e.to_string() })),
+        },
+        Ok(_) => Arc::clone(&state.shield),
+        Err(e) => return Json(serde_json::json!({ "status": "error", "error": e.to_string() })),
+    };
+
+    let mut node_params = if params.is_object() { params.clone() } else { serde_json::json!({}) };
+    node_params["instruction"] = serde_json::json!(task);
+    if !requirements.is_empty() {
+        node_params["requires"] = serde_json::json!(requirements);
+    }
+    let node = TaskNode {
+        id: "execute".to_string(),
+        task_type: task_type.clone(),
+        params: node_params,
+        invariants: vec![],
+        approval_gate: None,
+    };
+    let mut ranked = state.router.route_node(&node, &strategy, profile.as_ref());
+    let routed = ranked.len();
+    ranked.retain(|d| state.shield.kill_switch().check(Some(&d.provider_id), None).is_none());
+    if ranked.is_empty() && routed > 0 {
+        return Json(serde_json::json!({ "status": "killed", "error": "Every suitable provider is stopped by the kill switch" }));
+    }
+    if ranked.is_empty() {
+        if let Some((provider_id, retry_after)) = state.router.soonest_available() {
+            return Json(serde_json::json!({
+                "status": "rate_limited",
+                "provider_id": provider_id,
+                "retry_after_ms": retry_after.as_millis() as u64,
+            }));
+        }
+        return Json(serde_json::json!({ "status": "error", "error": "No suitable provider found" }));
+    }
+    let requirements = ranked[0].requirements.clone();
+
+    let (policy, headroom) = budget_headroom(&state, profile.as_ref(), max_cost_usd, run_id.as_deref(), plan_id.as_deref()).await;
+    let max_tokens = params.get("max_tokens").and_then(|v| v.as_u64());
+    let candidates = match state.router.apply_budget(ranked, &requirements, &task, max_tokens, &headroom, policy.on_exceed) {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            let mut ledger = state.ledger.lock().await;
+            let _ = ledger.append(serde_json::json!({
+                "event": "budget_refused",
+                "run_id": run_id,
+                "plan_id": plan_id,
+                "reason": e.to_string()
+            })).await;
+            return Json(serde_json::json!({ "status": "refused", "error": e.to_string() }));
+        }
+    };
+
+    let privacy_policy = match &profile {
+        Some(profile) => axial_router::privacy::PrivacyPolicy::from_profile(profile),
+        None => axial_router::privacy::PrivacyPolicy::default(),
+    };
+    // Redacted values become tokens kept in the run's vault and are put back in the response.
+    let vault = match run_vault(&state, run_id.as_deref().unwrap_or("default")).await {
+        Ok(vault) => vault,
+        Err(e) => return Json(serde_json::json!({ "status": "error", "error": e.to_string() })),
+    };
+    // Held until the new tokens are saved, so another request can't save in between.
+    let mut locked = vault.lock().await;
+    let guarded = match state.router.apply_privacy(&shield, &privacy_policy, candidates, &task, &params, Some(&mut locked)) {
+        Ok(guarded) => guarded,
+        Err(axial_router::RouterError::PrivacyBlocked(reason, mut record)) => {
+            record.run_id = run_id.clone();
+            record.plan_id = plan_id.clone();
+            let mut ledger = state.ledger.lock().await;
+            let _ = ledger.append(record.to_ledger_payload()).await;
+            return Json(serde_json::json!({ "status": "refused", "error": reason }));
+        }
+        Err(e) => return Json(serde_json::json!({ "status": "error", "error": e.to_string() })),
+    };
+    if guarded.record.action.is_some() {
+        let mut record = guarded.record.clone();
+        record.run_id = run_id.clone();
+        record.plan_id = plan_id.clone();
+        let mut ledger = state.ledger.lock().await;
+        let _ = ledger.append(record.to_ledger_payload()).await;
+    }
+    if guarded.redacted_prompt.is_some() {
+        if let Err(e) = locked.save() {
+            return Json(serde_json::json!({ "status": "error", "error": e.to_string() }));
+        }
+    }
+    drop(locked);
+
+    // Walk the fallback chain until a provider accepts the stream, replaying a cached
+    // response instead when there is one.
+    let mut opened = None;
+    let mut failures = Vec::new();
+    let mut retry_after: Option<std::time::Duration> = None;
+    for decision in &guarded.decisions {
+        let started = std::time::Instant::now();
+        let prompt = guarded.prompt_for(decision);
+        let params = guarded.params_for(decision);
+        if let Some((response, mut hit)) = state.router.cached_response(&decision.provider_id, prompt, params).await {
+            hit.run_id = run_id.clone();
+            hit.plan_id = plan_id.clone();
+            let mut ledger = state.ledger.lock().await;
+            let _ = ledger.append(hit.to_ledger_payload()).await;
+            let events = axial_router::ProviderEvent::from_response(&response).into_iter().map(Ok);
+            let stream: axial_router::ProviderStream = Box::pin(futures_util::stream::iter(events));
+            opened = Some((decision.clone(), stream, started, prompt.to_string(), params.clone(), true));
+            break;
+        }
+        match state.router.execute_stream(&decision.provider_id, prompt, params.clone()).await {
+            Ok(stream) => {
+                opened = Some((decision.clone(), stream, started, prompt.to_string(), params.clone(), false));
+                break;
+            }
+            Err(axial_router::RouterError::RateLimitExceeded { provider_id, retry_after: wait }) => {
+                // Saturated, not failing: the breaker and learned scores stay untouched.
+                retry_after = Some(retry_after.map_or(wait, |r| r.min(wait)));
+                failures.push(serde_json::json!({ "provider_id": provider_id, "error": "rate limited", "retry_after_ms": wait.as_millis() as u64 }));
+            }
+            Err(e) => {
+                record_outcome(&state, axial_router::learning::ProviderOutcome {
+                    provider_id: decision.provider_id.clone(),
+                    task_type: task_type.clone(),
+                    success: false,
+                    latency_ms: started.elapsed().as_millis() as u64,
+                    cost: 0.0,
+                    tokens: 0,
+                    invariants_passed: None,
+                    run_id: run_id.clone(),
+                    plan_id: plan_id.clone(),
+                    profile: profile_name.clone(),
+                }).await;
+                failures.push(serde_json::json!({ "provider_id": decision.provider_id, "error": e.to_string() }));
+            }
+        }
+    }
+    let (decision, mut stream, started, prompt, params, cached) = match opened {
+        Some(opened) => opened,
+        None => {
+            if let (Some(wait), true) = (retry_after, failures.iter().all(|f| f.get("retry_after_ms").is_some())) {
+                return Json(serde_json::json!({ "status": "rate_limited", "retry_after_ms": wait.as_millis() as u64, "attempts": failures }));
+            }
+            return Json(serde_json::json!({ "status": "error", "error": "All candidate providers failed", "attempts": failures }));
+        }
+    };
+
+    let stream_id = uuid::Uuid::new_v4().to_string();
+    let provider_id = decision.provider_id.clone();
+    let state_clone = Arc::clone(&state);
+    let stream_id_clone = stream_id.clone();
+
+    tokio::spawn(async move {
+        let mut seq = 0u64;
+        let mut success = true;
+        let mut usage = axial_router::Usage::default();
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+        let mut stop_reason = None;
+        // The provider only saw vault tokens; subscribers get the original values back.
+        let mut restorer = axial_shield::StreamRestorer::default();
+        let mut killed = false;
+        while let Some(item) = stream.next().await {
+            if let Some(entry) = state_clone.shield.kill_switch().check(Some(&provider_id), run_id.as_deref()) {
+                publish_event(&state_clone, format!("{}:{}", stream_id_clone, seq), serde_json::json!({
+                    "event": "provider_error",
+                    "stream_id": stream_id_clone,
+                    "provider_id": provider_id,
+                    "error": format!("Stopped by the kill switch ({})", entry.scope)
+                }));
+                killed = true;
+                break;
+            }
+            let mut events = Vec::new();
+            let payload = match item {
+                Ok(event) => {
+                    match event {
+                        axial_router::ProviderEvent::Token { text: chunk } => {
+                            text.push_str(&chunk);
+                            let restored = restorer.push(&chunk, &*vault.lock().await);
+                            if !restored.is_empty() {
+                                events.push(axial_router::ProviderEvent::Token { text: restored });
+                            }
+                        }
+                        axial_router::ProviderEvent::ToolCall { id, name, mut input } => {
+                            tool_calls.push(serde_json::json!({ "id": id, "name": name, "input": input }));
+                            vault.lock().await.restore_value(&mut input);
+                            events.push(axial_router::ProviderEvent::ToolCall { id, name, input });
+                        }
+                        axial_router::ProviderEvent::Usage { usage: reported } => {
+                            usage = reported.clone();
+                            events.push(axial_router::ProviderEvent::Usage { usage: reported });
+                        }
+                        axial_router::ProviderEvent::Done { stop_reason: reason } => {
+                            let rest = restorer.finish(&*vault.lock().await);
+                            if !rest.is_empty() {
+                                events.push(axial_router::ProviderEvent::Token { text: rest });
+                            }
+                            stop_reason = reason.clone();
+                            events.push(axial_router::ProviderEvent::Done { stop_reason: reason });
+                        }
+                    }
+                    events.into_iter().map(|event| serde_json::json!({
+                        "event": "provider_event",
+                        "stream_id": stream_id_clone,
+                        "provider_id": provider_id,
+                        "data": event
+                    })).collect()
+                }
+                Err(e) => {
+                    success = false;
+                    vec![serde_json::json!({
+                        "event": "provider_error",
+                        "stream_id": stream_id_clone,
+                        "provider_id": provider_id,
+                        "error": e.to_string()
+                    })]
+                }
+            };
+            for payload in payload {
+                publish_event(&state_clone, format!("{}:{}", stream_id_clone, seq), payload);
+                seq += 1;
+            }
+        }
+
+        // A replayed response cost nothing and says nothing new about the provider; a killed
+        // stream says nothing about it either.
+        if cached || killed {
+            return;
+        }
+        // The token bucket was charged an estimate when the stream opened; settle it.
+        if usage.total_tokens() > 0 {
+            let estimated = axial_router::Router::estimate_call_tokens(&prompt, &params);
+            state_clone.router.reconcile_tokens(&provider_id, estimated, usage.total_tokens());
+        }
+        if success {
+            let response = serde_json::json!({
+                "response": text,
+                "tool_calls": tool_calls,
+                "usage": usage,
+                "stop_reason": stop_reason,
+            });
+            state_clone.router.store_response(&provider_id, &prompt, &params, &response).await;
+        }
+
+        let cost = state_clone.router.compute_cost(&provider_id, &requirements, &usage).unwrap_or(0.0);
+        record_outcome(&state_clone, axial_router::learning::ProviderOutcome {
+            provider_id,
+            task_type,
+            success,
+            latency_ms: started.elapsed().as_millis() as u64,
+            cost,
+            tokens: usage.total_tokens(),
+            invariants_passed: None,
+            run_id,
+            plan_id,
+            profile: profile_name,
+        }).await;
+    });
+
+    Json(serde_json::json!({
+        "status": "started",
+        "stream_id": stream_id,
+        "provider_id": decision.provider_id,
+        "explanation": decision.explanation,
+        "estimated_cost": decision.estimated_cost,
+        "shielded": decision.shielded,
+        "cached": cached,
+        "failed_attempts": failures
+    }))
+}
+
+/// Persists a provider outcome and folds it into the router's learned scores and the spend
+/// budgets are checked against.
+async fn record_outcome(state: &AppState, outcome: axial_router::learning::ProviderOutcome) {
+    let mut ledger = state.ledger.lock().await;
+    let _ = ledger.append(outcome.to_ledger_payload()).await;
+    state.router.record_outcome(&outcome);
+    state.spend.lock().await.record(chrono::Utc::now(), &outcome);
+}
+
+fn publish_event(state: &AppState, id: String, payload: serde_json::Value) {
+    // A send error only means no UI is subscribed right now.
+    let _ = state.event_tx.send(EventPacket {
+        id,
+        timestamp: chrono::Utc::now(),
+        payload,
+    });
+}
+
+/// Server-sent event feed of everything published on `event_tx`, for the Command Center.
+async fn stream_events(
+    State(state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let rx = state.event_tx.subscribe();
+    let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(packet) => {
+                    let event = Event::default()
+                        .id(packet.id.clone())
+                        .json_data(&packet)
+                        .unwrap_or_default();
+                    return Some((Ok(event), rx));
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn handle_approve(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<ApprovalRequest>,
+) -> Json<serde_json::Value> {
+    let mut responses = state.gate_responses.lock().await;
+    responses.insert(payload.gate_id.clone(), payload.approved);
+    
+    let mut ledger = state.ledger.lock().await;
+    ledger.append(serde_json::json!({
+        "event": "approval_gate_response",
+        "gate_id": payload.gate_id,
+        "approved": payload.approved
+    })).await.unwrap();
+
+    Json(serde_json::json!({ "status": "acknowledged" }))
+}
+
+#[derive(Deserialize)]
+struct LedgerQuery {
+    query: String,
+}
+
+#[derive(Deserialize)]
+struct SemanticSearchRequest {
+    text: String,
+    limit: usize,
+}
+
+async fn query_ledger(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<LedgerQuery>,
+) -> Json<Vec<axial_core::schemas::LedgerEntry>> {
+    let ledger = state.ledger.lock().await;
+    let res = ledger.query(&payload.query).await.unwrap_or_default();
+    Json(res)
+}
+
+async fn semantic_search(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SemanticSearchRequest>,
+) -> Json<Vec<axial_core::schemas::LedgerEntry>> {
+    let ledger = state.ledger.lock().await;
+    let res = ledger.search_semantic(&payload.text, payload.limit).await.unwrap_or_default();
+    Json(res)
+}
+
+async fn get_status(State(state): State<Arc<AppState>>) -> Json<DaemonStatus> {
+    // Note: This is an approximation for v1-max
+    Json(DaemonStatus {
+        status: "Running".to_string(),
+        sessions_count: 0, 
+        breakers: state.router.breaker_states(),
+        health: state.router.health_states(),
+        rate_limits: state.router.rate_limit_states(),
+        kill_switch: state.shield.kill_switch().engaged(),
+    })
+}
+
+async fn spawn_pty(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SpawnRequest>,
+) -> Result<Json<SpawnResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let error = |status, error: String| (status, Json(serde_json::json!({ "status": "error", "error": error })));
+    if let Some(entry) = state.shield.kill_switch().check(None, payload.run_id.as_deref()) {
+        return Err(error(StatusCode::FORBIDDEN, format!("The shield kill switch is engaged ({})", entry.scope)));
+    }
+    let mut manager = state.pty_manager.lock().await;
+    let filter = crate::RedactingPtyFilter::new(&state.shield)
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, format!("Could not set up output redaction: {}", e)))?;
+    let session = manager.spawn_filtered(&payload.command, payload.run_id.clone(), Some(Box::new(filter)))
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    
+    Ok(Json(SpawnResponse {
+        session_id: session.id,
+    }))
+}
+
+async fn replay_pty(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Json<Vec<axial_pty::PtyEvent>> {
+    let mut manager = state.pty_manager.lock().await;
+    let events = manager.replay(&id, None).unwrap_or_default();
+    Json(events)
+}
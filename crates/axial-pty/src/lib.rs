@@ -1,112 +1,245 @@
-use portable_pty::{native_pty_system, CommandBuilder, PtySize};
-use std::io::{Read, Write};
-use std::sync::{Arc, Mutex};
-use anyhow::Result;
-use tokio::sync::mpsc;
-
-use portable_pty::{native_pty_system, CommandBuilder, PtySize};
-use std::io::{Read, Write};
-use std::sync::{Arc, Mutex};
-use anyhow::Result;
-use chrono::{DateTime, Utc};
-use serde::{Serialize, Deserialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PtyEvent {
-    pub timestamp: DateTime<Utc>,
-    pub data: Vec<u8>,
-}
-
-pub struct Session {
-    pub id: String,
-    pub events: Arc<Mutex<Vec<PtyEvent>>>,
-    pub child_pid: u32,
-}
-
-pub struct PtyManager {
-    pub sessions: std::collections::HashMap<String, Arc<Session>>,
-}
-
-impl PtyManager {
-    pub fn new() -> Self {
-        Self { sessions: std::collections::HashMap::new() }
-    }
-
-    pub fn spawn(&mut self, command: &str) -> Result<Arc<Session>> {
-        let pty_system = native_pty_system();
-        let pair = pty_system.open_pty(PtySize {
-            rows: 24,
-            cols: 80,
-            pixel_width: 0,
-            pixel_height: 0,
-        })?;
-
-        let cmd = CommandBuilder::new(command);
-        let child = pair.slave.spawn_command(cmd)?;
-        let pid = child.process_id().unwrap_or(0);
-
-        let mut reader = pair.master.try_clone_reader()?;
-        let events = Arc::new(Mutex::new(Vec::new()));
-        let events_clone = Arc::clone(&events);
-
-        std::thread::spawn(move || {
-            let mut buffer = [0u8; 4096];
-            while let Ok(n) = reader.read(&mut buffer) {
-                if n == 0 { break; }
-                let event = PtyEvent {
-                    timestamp: Utc::now(),
-                    data: buffer[..n].to_vec(),
-                };
-                let mut lock = events_clone.lock().unwrap();
-                lock.push(event);
-            }
-        });
-
-        let session = Arc::new(Session {
-            id: uuid::Uuid::new_v4().to_string(),
-            events,
-            child_pid: pid,
-        });
-        
-        self.sessions.insert(session.id.clone(), Arc::clone(&session));
-        Ok(session)
-    }
-
-    pub fn replay(&self, session_id: &str, start_time: Option<DateTime<Utc>>) -> Result<Vec<PtyEvent>> {
-        let session = self.sessions.get(session_id).ok_or_else(|| anyhow::anyhow!("Session not found"))?;
-        let lock = session.events.lock().unwrap();
-        
-        let filtered = lock.iter()
-            .filter(|e| start_time.map_or(true, |t| e.timestamp >= t))
-            .cloned()
-            .collect();
-            
-        Ok(filtered)
-    }
-
-    pub fn checkpoint(&self, session_id: &str) -> Result<()> {
-        let session = self.sessions.get(session_id).ok_or_else(|| anyhow::anyhow!("Session not found"))?;
-        println!("AXIAL Neuro-PTY: Attempting CRIU checkpoint for PID {}...", session.child_pid);
-        
-        // v1-max: Check for CRIU availability
-        if std::process::Command::new("criu").arg("--version").output().is_ok() {
-            let status = std::process::Command::new("sudo")
-                .arg("criu")
-                .arg("dump")
-                .arg("-t")
-                .arg(session.child_pid.to_string())
-                .arg("--images-dir")
-                .arg(format!("/tmp/axial_checkpoint_{}", session_id))
-                .arg("--shell-job")
-                .status()?;
-            
-            if status.success() {
-                return Ok(());
-            }
-        }
-
-        // Fallback path
-        println!("AXIAL Neuro-PTY: CRIU failed or not supported. Using fallback state capture...");
-        Ok(())
-    }
-}
+use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+use anyhow::Result;
+use tokio::sync::mpsc;
+
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, PtySize};
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+/// How long output must be quiet before an `OutputFilter` is asked to `settle`.
+pub const FILTER_IDLE_FLUSH: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Rewrites PTY output before it is recorded, e.g. to redact secrets. A filter may hold
+/// bytes back across chunks.
+pub trait OutputFilter: Send {
+    fn filter(&mut self, chunk: &[u8]) -> Vec<u8>;
+    /// Called when the session goes quiet: returns the held bytes that can't be part of
+    /// something still being written, which may arrive any time later.
+    fn settle(&mut self) -> Vec<u8>;
+    /// Called when the session ends: returns everything still held.
+    fn flush(&mut self) -> Vec<u8>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PtyEvent {
+    pub timestamp: DateTime<Utc>,
+    pub data: Vec<u8>,
+}
+
+pub struct Session {
+    pub id: String,
+    pub events: Arc<Mutex<Vec<PtyEvent>>>,
+    pub child_pid: u32,
+    /// The run this session belongs to, so a run-scoped kill switch can find it.
+    pub run_id: Option<String>,
+    killer: Mutex<Box<dyn ChildKiller + Send + Sync>>,
+}
+
+impl Session {
+    /// Terminates the session's child process.
+    pub fn kill(&self) -> Result<()> {
+        self.killer.lock().unwrap().kill()?;
+        Ok(())
+    }
+}
+
+pub struct PtyManager {
+    pub sessions: std::collections::HashMap<String, Arc<Session>>,
+}
+
+impl PtyManager {
+    pub fn new() -> Self {
+        Self { sessions: std::collections::HashMap::new() }
+    }
+
+    pub fn spawn(&mut self, command: &str) -> Result<Arc<Session>> {
+        self.spawn_filtered(command, None, None)
+    }
+
+    /// Like `spawn`, tagging the session with `run_id` and passing all output through
+    /// `filter` before it is recorded.
+    pub fn spawn_filtered(&mut self, command: &str, run_id: Option<String>, filter: Option<Box<dyn OutputFilter>>) -> Result<Arc<Session>> {
+        let pty_system = native_pty_system();
+        let pair = pty_system.open_pty(PtySize {
+            rows: 24,
+            cols: 80,
+            pixel_width: 0,
+            pixel_height: 0,
+        })?;
+
+        let cmd = CommandBuilder::new(command);
+        let child = pair.slave.spawn_command(cmd)?;
+        let pid = child.process_id().unwrap_or(0);
+        let killer = Mutex::new(child.clone_killer());
+
+        let mut reader = pair.master.try_clone_reader()?;
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = Arc::clone(&events);
+
+        let record = move |data: Vec<u8>| {
+            if data.is_empty() { return; }
+            let event = PtyEvent {
+                timestamp: Utc::now(),
+                data,
+            };
+            let mut lock = events_clone.lock().unwrap();
+            lock.push(event);
+        };
+
+        match filter {
+            None => {
+                std::thread::spawn(move || {
+                    let mut buffer = [0u8; 4096];
+                    while let Ok(n) = reader.read(&mut buffer) {
+                        if n == 0 { break; }
+                        record(buffer[..n].to_vec());
+                    }
+                });
+            }
+            Some(mut filter) => {
+                // Reads block, so a second thread owns the filter and flushes it when idle.
+                let (tx, rx) = std::sync::mpsc::channel::<Vec<u8>>();
+                std::thread::spawn(move || {
+                    let mut buffer = [0u8; 4096];
+                    while let Ok(n) = reader.read(&mut buffer) {
+                        if n == 0 || tx.send(buffer[..n].to_vec()).is_err() { break; }
+                    }
+                });
+                std::thread::spawn(move || loop {
+                    match rx.recv_timeout(FILTER_IDLE_FLUSH) {
+                        Ok(chunk) => record(filter.filter(&chunk)),
+                        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => record(filter.settle()),
+                        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                            record(filter.flush());
+                            break;
+                        }
+                    }
+                });
+            }
+        }
+
+        let session = Arc::new(Session {
+            id: uuid::Uuid::new_v4().to_string(),
+            events,
+            child_pid: pid,
+            run_id,
+            killer,
+        });
+        
+        self.sessions.insert(session.id.clone(), Arc::clone(&session));
+        Ok(session)
+    }
+
+    /// Kills the child of every session matching `filter`, returning the ids of the
+    /// sessions signalled.
+    pub fn terminate(&self, filter: impl Fn(&Session) -> bool) -> Vec<String> {
+        self.sessions.values()
+            .filter(|session| filter(session))
+            .filter(|session| session.kill().is_ok())
+            .map(|session| session.id.clone())
+            .collect()
+    }
+
+    pub fn replay(&self, session_id: &str, start_time: Option<DateTime<Utc>>) -> Result<Vec<PtyEvent>> {
+        let session = self.sessions.get(session_id).ok_or_else(|| anyhow::anyhow!("Session not found"))?;
+        let lock = session.events.lock().unwrap();
+        
+        let filtered = lock.iter()
+            .filter(|e| start_time.map_or(true, |t| e.timestamp >= t))
+            .cloned()
+            .collect();
+            
+        Ok(filtered)
+    }
+
+    pub fn checkpoint(&self, session_id: &str) -> Result<()> {
+        let session = self.sessions.get(session_id).ok_or_else(|| anyhow::anyhow!("Session not found"))?;
+        println!("AXIAL Neuro-PTY: Attempting CRIU checkpoint for PID {}...", session.child_pid);
+        
+        // v1-max: Check for CRIU availability
+        if std::process::Command::new("criu").arg("--version").output().is_ok() {
+            let status = std::process::Command::new("sudo")
+                .arg("criu")
+                .arg("dump")
+                .arg("-t")
+                .arg(session.child_pid.to_string())
+                .arg("--images-dir")
+                .arg(format!("/tmp/axial_checkpoint_{}", session_id))
+                .arg("--shell-job")
+                .status()?;
+            
+            if status.success() {
+                return Ok(());
+            }
+        }
+
+        // Fallback path
+        println!("AXIAL Neuro-PTY: CRIU failed or not supported. Using fallback state capture...");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replaces `hunter2`, holding back the word being written like a redacting filter does.
+    #[derive(Default)]
+    struct MaskingFilter {
+        pending: String,
+    }
+
+    impl MaskingFilter {
+        fn release(&mut self, upto: usize) -> Vec<u8> {
+            let released: String = self.pending.drain(..upto).collect();
+            released.replace("hunter2", "[REDACTED]").into_bytes()
+        }
+    }
+
+    impl OutputFilter for MaskingFilter {
+        fn filter(&mut self, chunk: &[u8]) -> Vec<u8> {
+            self.pending.push_str(&String::from_utf8_lossy(chunk));
+            Vec::new()
+        }
+
+        fn settle(&mut self) -> Vec<u8> {
+            let upto = self.pending.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+            self.release(upto)
+        }
+
+        fn flush(&mut self) -> Vec<u8> {
+            self.release(self.pending.len())
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_secrets_written_slower_than_the_idle_flush_are_still_filtered() {
+        // The pause mid-secret is ten times `FILTER_IDLE_FLUSH`.
+        let script = std::env::temp_dir().join(format!("axial-pty-test-{}.sh", std::process::id()));
+        std::fs::write(&script, "#!/bin/sh\nprintf 'password hun'\nsleep 0.5\nprintf 'ter2 ok\\n'\n").unwrap();
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let mut manager = PtyManager::new();
+        let session = manager.spawn_filtered(&script.to_string_lossy(), None, Some(Box::<MaskingFilter>::default())).unwrap();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        let output = loop {
+            let output: Vec<u8> = session.events.lock().unwrap().iter().flat_map(|e| e.data.clone()).collect();
+            let output = String::from_utf8_lossy(&output).into_owned();
+            if output.contains("ok") || std::time::Instant::now() > deadline {
+                break output;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        };
+        let _ = std::fs::remove_file(&script);
+        assert!(output.contains("password [REDACTED] ok"), "{:?}", output);
+    }
+}
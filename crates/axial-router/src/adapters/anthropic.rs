@@ -0,0 +1,421 @@
+use crate::{Provider, ProviderInfo, Capability, Usage, ProviderEvent, ProviderStream};
+use async_trait::async_trait;
+use anyhow::{Result, anyhow};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+
+pub const ANTHROPIC_API_URL: &str = "https://api.anthropic.com";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const DEFAULT_MAX_TOKENS: u64 = 4096;
+
+/// Input and output USD per 1k tokens for `model`. A model is billed the same whatever it
+/// is used for; unknown models are priced as Sonnet.
+fn price_per_1k(model: &str) -> (f64, f64) {
+    if model.contains("opus-4-5") {
+        (0.005, 0.025)
+    } else if model.contains("opus") {
+        (0.015, 0.075)
+    } else if model.contains("haiku-4-5") {
+        (0.001, 0.005)
+    } else if model.contains("haiku") {
+        (0.0008, 0.004)
+    } else {
+        (0.003, 0.015)
+    }
+}
+
+/// Provider backed by the Anthropic Messages API (`/v1/messages`).
+///
+/// Recognised `params`: `system`, `messages`, `tools`, `tool_choice`, `max_tokens`,
+/// `temperature`, `stop_sequences`, `stream` and `cache` (marks the system prompt and
+/// tool definitions with an ephemeral `cache_control` breakpoint).
+pub struct AnthropicProvider {
+    pub model: String,
+    pub api_key: String,
+    pub base_url: String,
+}
+
+impl AnthropicProvider {
+    pub fn build_request(&self, task: &str, params: &Value) -> Value {
+        let cache = params.get("cache").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let messages = params.get("messages").cloned()
+            .unwrap_or_else(|| json!([{ "role": "user", "content": task }]));
+
+        let mut body = json!({
+            "model": self.model,
+            "max_tokens": params.get("max_tokens").and_then(|v| v.as_u64()).unwrap_or(DEFAULT_MAX_TOKENS),
+            "messages": messages,
+        });
+
+        if let Some(system) = params.get("system").and_then(|v| v.as_str()) {
+            let mut block = json!({ "type": "text", "text": system });
+            if cache {
+                block["cache_control"] = json!({ "type": "ephemeral" });
+            }
+            body["system"] = json!([block]);
+        }
+
+        if let Some(tools) = params.get("tools").and_then(|v| v.as_array()) {
+            let mut tools = tools.clone();
+            // A breakpoint on the last tool caches every tool definition before it.
+            if cache {
+                if let Some(last) = tools.last_mut() {
+                    last["cache_control"] = json!({ "type": "ephemeral" });
+                }
+            }
+            body["tools"] = Value::Array(tools);
+        }
+
+        for key in ["tool_choice", "temperature", "stop_sequences"] {
+            if let Some(v) = params.get(key) {
+                body[key] = v.clone();
+            }
+        }
+
+        if params.get("stream").and_then(|v| v.as_bool()).unwrap_or(false) {
+            body["stream"] = json!(true);
+        }
+
+        body
+    }
+
+    async fn send(&self, body: &Value) -> Result<reqwest::Response> {
+        let res = reqwest::Client::new().post(format!("{}/v1/messages", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(body)
+            .send().await?;
+        if res.status().is_success() {
+            Ok(res)
+        } else {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            Err(anyhow!("Anthropic failed with status {}: {}", status, text))
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for AnthropicProvider {
+    fn model(&self) -> Option<String> {
+        Some(self.model.clone())
+    }
+
+    /// Lists models, which checks reachability and the API key without spending tokens.
+    async fn health_check(&self) -> Result<()> {
+        let res = reqwest::Client::new().get(format!("{}/v1/models", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .send().await?;
+        if res.status().is_success() {
+            Ok(())
+        } else {
+            Err(anyhow!("Anthropic /v1/models failed with status: {}", res.status()))
+        }
+    }
+
+    fn info(&self) -> ProviderInfo {
+        let (input, output) = price_per_1k(&self.model);
+        let capability = |name: &str, score| Capability {
+            name: name.to_string(),
+            score,
+            cost_per_1k_tokens: input,
+            output_cost_per_1k_tokens: output,
+        };
+        ProviderInfo {
+            id: format!("anthropic-{}", self.model),
+            name: format!("Anthropic ({})", self.model),
+            capabilities: vec![
+                capability("text-generation", 95),
+                capability("code-editing", 95),
+                capability("complex-reasoning", 97),
+                capability("tool-use", 95),
+                capability("long-context", 95),
+            ],
+            latency_ms: 800,
+            privacy_level: crate::PrivacyLevel::Cloud,
+        }
+    }
+
+    async fn execute(&self, task: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        let body = self.build_request(task, &params);
+        let mut res = self.send(&body).await?;
+
+        if body.get("stream").is_none() {
+            let message: Value = res.json().await?;
+            return Ok(normalize_message(&message));
+        }
+
+        let mut acc = MessageAccumulator::default();
+        let mut buffer = Vec::new();
+        while let Some(chunk) = res.chunk().await? {
+            buffer.extend_from_slice(&chunk);
+            while let Some((event, data)) = next_sse_frame(&mut buffer) {
+                acc.apply(&event, &data)?;
+            }
+        }
+        Ok(acc.finish(&self.model))
+    }
+
+    async fn execute_stream(&self, task: &str, mut params: serde_json::Value) -> Result<ProviderStream> {
+        params["stream"] = json!(true);
+        let body = self.build_request(task, &params);
+        let res = self.send(&body).await?;
+
+        let state = SseState { res, buffer: Vec::new(), acc: MessageAccumulator::default(), pending: VecDeque::new(), done: false };
+        let stream = futures_util::stream::try_unfold(state, |mut state| async move {
+            loop {
+                if let Some(event) = state.pending.pop_front() {
+                    return Ok(Some((event, state)));
+                }
+                if state.done {
+                    return Ok(None);
+                }
+                match state.res.chunk().await? {
+                    Some(chunk) => {
+                        state.buffer.extend_from_slice(&chunk);
+                        while let Some((event, data)) = next_sse_frame(&mut state.buffer) {
+                            let events = state.acc.apply(&event, &data)?;
+                            state.pending.extend(events);
+                        }
+                    }
+                    None => state.done = true,
+                }
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+}
+
+struct SseState {
+    res: reqwest::Response,
+    buffer: Vec<u8>,
+    acc: MessageAccumulator,
+    pending: VecDeque<ProviderEvent>,
+    done: bool,
+}
+
+/// Flattens a Messages API response into the router's response shape:
+/// `{ "response", "tool_calls", "stop_reason", "model", "usage" }`.
+pub fn normalize_message(message: &Value) -> Value {
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+    for block in message["content"].as_array().into_iter().flatten() {
+        match block["type"].as_str() {
+            Some("text") => text.push_str(block["text"].as_str().unwrap_or("")),
+            Some("tool_use") => tool_calls.push(json!({
+                "id": block["id"],
+                "name": block["name"],
+                "input": block["input"],
+            })),
+            _ => {}
+        }
+    }
+
+    let usage: Usage = serde_json::from_value(message["usage"].clone()).unwrap_or_default();
+    json!({
+        "response": text,
+        "tool_calls": tool_calls,
+        "stop_reason": message["stop_reason"],
+        "model": message["model"],
+        "usage": usage,
+    })
+}
+
+/// Pops the next complete server-sent event off `buffer`, returning its `event` name and
+/// parsed `data` payload. Bytes are buffered so multi-byte characters split across
+/// network chunks are decoded intact.
+pub(crate) fn next_sse_frame(buffer: &mut Vec<u8>) -> Option<(String, Value)> {
+    loop {
+        let pos = buffer.windows(2).position(|w| w == b"\n\n")?;
+        let frame: Vec<u8> = buffer.drain(..pos + 2).collect();
+        if let Some(parsed) = parse_sse_frame(&String::from_utf8_lossy(&frame)) {
+            return Some(parsed);
+        }
+    }
+}
+
+fn parse_sse_frame(frame: &str) -> Option<(String, Value)> {
+    let mut event = None;
+    let mut data = String::new();
+    for line in frame.lines() {
+        if let Some(rest) = line.strip_prefix("event:") {
+            event = Some(rest.trim().to_string());
+        } else if let Some(rest) = line.strip_prefix("data:") {
+            data.push_str(rest.trim());
+        }
+    }
+    let data: Value = serde_json::from_str(&data).ok()?;
+    let event = event.or_else(|| data["type"].as_str().map(str::to_string))?;
+    Some((event, data))
+}
+
+/// Rebuilds a complete message from a `stream: true` event sequence, emitting
+/// `ProviderEvent`s as each piece becomes available.
+#[derive(Default)]
+pub(crate) struct MessageAccumulator {
+    text: String,
+    tool_calls: Vec<(String, String, String)>,
+    open_tool: Option<usize>,
+    stop_reason: Option<String>,
+    model: Option<String>,
+    usage: Usage,
+}
+
+impl MessageAccumulator {
+    pub(crate) fn apply(&mut self, event: &str, data: &Value) -> Result<Vec<ProviderEvent>> {
+        let mut events = Vec::new();
+        match event {
+            "message_start" => {
+                let message = &data["message"];
+                self.model = message["model"].as_str().map(str::to_string);
+                if let Ok(usage) = serde_json::from_value::<Usage>(message["usage"].clone()) {
+                    self.usage = usage;
+                }
+            }
+            "content_block_start" => {
+                let block = &data["content_block"];
+                if block["type"] == "tool_use" {
+                    self.tool_calls.push((
+                        block["id"].as_str().unwrap_or("").to_string(),
+                        block["name"].as_str().unwrap_or("").to_string(),
+                        String::new(),
+                    ));
+                    self.open_tool = Some(self.tool_calls.len() - 1);
+                }
+            }
+            "content_block_delta" => {
+                let delta = &data["delta"];
+                match delta["type"].as_str() {
+                    Some("text_delta") => {
+                        let text = delta["text"].as_str().unwrap_or("");
+                        self.text.push_str(text);
+                        events.push(ProviderEvent::Token { text: text.to_string() });
+                    }
+                    Some("input_json_delta") => {
+                        if let Some(call) = self.open_tool.and_then(|i| self.tool_calls.get_mut(i)) {
+                            call.2.push_str(delta["partial_json"].as_str().unwrap_or(""));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            "content_block_stop" => {
+                if let Some((id, name, input)) = self.open_tool.take().and_then(|i| self.tool_calls.get(i)) {
+                    events.push(ProviderEvent::ToolCall { id: id.clone(), name: name.clone(), input: parse_tool_input(input) });
+                }
+            }
+            "message_delta" => {
+                if let Some(reason) = data["delta"]["stop_reason"].as_str() {
+                    self.stop_reason = Some(reason.to_string());
+                }
+                if let Some(out) = data["usage"]["output_tokens"].as_u64() {
+                    self.usage.output_tokens = out;
+                }
+                events.push(ProviderEvent::Usage { usage: self.usage.clone() });
+            }
+            "message_stop" => {
+                events.push(ProviderEvent::Done { stop_reason: self.stop_reason.clone() });
+            }
+            "error" => {
+                return Err(anyhow!("Anthropic stream error: {}", data["error"]["message"].as_str().unwrap_or("unknown")));
+            }
+            _ => {}
+        }
+        Ok(events)
+    }
+
+    pub(crate) fn finish(self, model: &str) -> Value {
+        let tool_calls: Vec<Value> = self.tool_calls.iter().map(|(id, name, input)| {
+            json!({ "id": id, "name": name, "input": parse_tool_input(input) })
+        }).collect();
+
+        json!({
+            "response": self.text,
+            "tool_calls": tool_calls,
+            "stop_reason": self.stop_reason,
+            "model": self.model.unwrap_or_else(|| model.to_string()),
+            "usage": self.usage,
+        })
+    }
+}
+
+fn parse_tool_input(input: &str) -> Value {
+    if input.is_empty() {
+        json!({})
+    } else {
+        serde_json::from_str(input).unwrap_or_else(|_| Value::String(input.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_emits_events_and_accumulates_message() {
+        let frames = [
+            "event: message_start\ndata: {\"type\":\"message_start\",\"message\":{\"model\":\"claude-test\",\"usage\":{\"input_tokens\":12,\"output_tokens\":1,\"cache_read_input_tokens\":100}}}\n\n",
+            "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hello\"}}\n\n",
+            "event: content_block_start\ndata: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"tool_use\",\"id\":\"tu_1\",\"name\":\"read_file\",\"input\":{}}}\n\n",
+            "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"path\\\":\"}}\n\n",
+            "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"\\\"a.rs\\\"}\"}}\n\n",
+            "event: content_block_stop\ndata: {\"type\":\"content_block_stop\",\"index\":1}\n\n",
+            "event: message_delta\ndata: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\"},\"usage\":{\"output_tokens\":30}}\n\n",
+            "event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n",
+        ];
+
+        let mut buffer: Vec<u8> = frames.concat().into_bytes();
+        let mut acc = MessageAccumulator::default();
+        let mut events = Vec::new();
+        while let Some((event, data)) = next_sse_frame(&mut buffer) {
+            events.extend(acc.apply(&event, &data).unwrap());
+        }
+        assert_eq!(events[0], ProviderEvent::Token { text: "Hello".to_string() });
+        assert!(matches!(&events[1], ProviderEvent::ToolCall { name, .. } if name == "read_file"));
+        assert!(matches!(&events[2], ProviderEvent::Usage { usage } if usage.output_tokens == 30));
+        assert!(matches!(&events[3], ProviderEvent::Done { stop_reason: Some(r) } if r == "tool_use"));
+
+        let out = acc.finish("fallback");
+        assert_eq!(out["response"], "Hello");
+        assert_eq!(out["model"], "claude-test");
+        assert_eq!(out["stop_reason"], "tool_use");
+        assert_eq!(out["tool_calls"][0]["input"]["path"], "a.rs");
+        assert_eq!(out["usage"]["input_tokens"], 12);
+        assert_eq!(out["usage"]["output_tokens"], 30);
+        assert_eq!(out["usage"]["cache_read_input_tokens"], 100);
+
+        // Output tokens are priced at their own rate, cache reads at a tenth of the input rate.
+        let usage: Usage = serde_json::from_value(out["usage"].clone()).unwrap();
+        let expected = (12.0 * 0.003 + 100.0 * 0.1 * 0.003 + 30.0 * 0.015) / 1000.0;
+        assert!((usage.cost(0.003, 0.015) - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cache_marks_system_and_last_tool() {
+        let provider = AnthropicProvider {
+            model: "claude-test".to_string(),
+            api_key: String::new(),
+            base_url: ANTHROPIC_API_URL.to_string(),
+        };
+        let body = provider.build_request("hi", &json!({
+            "system": "You are AXIAL.",
+            "tools": [{ "name": "a" }, { "name": "b" }],
+            "cache": true,
+        }));
+
+        assert_eq!(body["system"][0]["cache_control"]["type"], "ephemeral");
+        assert!(body["tools"][0].get("cache_control").is_none());
+        assert_eq!(body["tools"][1]["cache_control"]["type"], "ephemeral");
+        assert_eq!(body["messages"][0]["content"], "hi");
+    }
+
+    #[test]
+    fn test_every_capability_is_priced_at_the_models_rate() {
+        let info = |model: &str| AnthropicProvider { model: model.to_string(), api_key: String::new(), base_url: String::new() }.info();
+        let sonnet = info("claude-sonnet-4-5");
+        assert!(sonnet.capabilities.iter().all(|c| (c.cost_per_1k_tokens, c.output_cost_per_1k_tokens) == (0.003, 0.015)));
+        assert_eq!(info("claude-opus-4-1").capabilities[0].output_cost_per_1k_tokens, 0.075);
+    }
+}
@@ -1,2 +1,3 @@
-pub mod ollama;
-pub mod openai;
+pub mod ollama;
+pub mod openai;
+pub mod anthropic;
@@ -1,44 +1,229 @@
-use crate::{Provider, ProviderInfo, Capability};
-use async_trait::async_trait;
-use anyhow::{Result, anyhow};
-use serde_json::json;
-
-pub struct OllamaProvider {
-    pub model: String,
-    pub base_url: String,
-}
-
-#[async_trait]
-impl Provider for OllamaProvider {
-    fn info(&self) -> ProviderInfo {
-        ProviderInfo {
-            id: format!("ollama-{}", self.model),
-            name: format!("Ollama ({})", self.model),
-            capabilities: vec![
-                Capability { name: "text-generation".to_string(), score: 70, cost_per_1k_tokens: 0.0 },
-                Capability { name: "code-editing".to_string(), score: 60, cost_per_1k_tokens: 0.0 },
-                Capability { name: "local-privacy".to_string(), score: 100, cost_per_1k_tokens: 0.0 },
-            ],
-            latency_ms: 100,
-            privacy_level: crate::PrivacyLevel::Local,
-        }
-    }
-
-    async fn execute(&self, task: &str, _params: serde_json::Value) -> Result<serde_json::Value> {
-        let client = reqwest::Client::new();
-        let res = client.post(format!("{}/api/generate", self.base_url))
-            .json(&json!({
-                "model": self.model,
-                "prompt": task,
-                "stream": false
-            }))
-            .send().await?;
-
-        if res.status().is_success() {
-            let body: serde_json::Value = res.json().await?;
-            Ok(body)
-        } else {
-            Err(anyhow!("Ollama failed with status: {}", res.status()))
-        }
-    }
-}
+use crate::{Provider, ProviderInfo, Capability, ProviderEvent, ProviderStream, Usage};
+use async_trait::async_trait;
+use anyhow::{Result, anyhow};
+use serde_json::json;
+use std::collections::VecDeque;
+
+pub const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+
+/// Names of the models installed on the Ollama server, from `/api/tags`. The implicit
+/// `:latest` tag is dropped so `llama3:latest` is registered as `llama3`.
+pub async fn list_models(base_url: &str) -> Result<Vec<String>> {
+    let res = reqwest::get(format!("{}/api/tags", base_url)).await?;
+    if !res.status().is_success() {
+        return Err(anyhow!("Ollama /api/tags failed with status: {}", res.status()));
+    }
+    let body: serde_json::Value = res.json().await?;
+    let mut models: Vec<String> = body["models"].as_array()
+        .map(|models| models.iter()
+            .filter_map(|m| m["name"].as_str().or(m["model"].as_str()))
+            .map(|name| name.strip_suffix(":latest").unwrap_or(name).to_string())
+            .collect())
+        .unwrap_or_default();
+    models.sort();
+    models.dedup();
+    Ok(models)
+}
+
+/// One provider per installed model.
+pub async fn discover(base_url: &str) -> Result<Vec<OllamaProvider>> {
+    Ok(list_models(base_url).await?
+        .into_iter()
+        .map(|model| OllamaProvider { model, base_url: base_url.to_string() })
+        .collect())
+}
+
+pub struct OllamaProvider {
+    pub model: String,
+    pub base_url: String,
+}
+
+impl OllamaProvider {
+    /// `/api/generate` body. `system`, `format` (`"json"` or a JSON schema) and `temperature`
+    /// are passed through from the call params.
+    fn generate_body(&self, task: &str, params: &serde_json::Value, stream: bool) -> serde_json::Value {
+        let mut body = json!({
+            "model": self.model,
+            "prompt": task,
+            "stream": stream
+        });
+        for key in ["system", "format"] {
+            if let Some(value) = params.get(key) {
+                body[key] = value.clone();
+            }
+        }
+        if let Some(temperature) = params.get("temperature") {
+            body["options"] = json!({ "temperature": temperature });
+        }
+        body
+    }
+}
+
+#[async_trait]
+impl Provider for OllamaProvider {
+    fn model(&self) -> Option<String> {
+        Some(self.model.clone())
+    }
+
+    /// Up when the server answers `/api/tags` and still has this model installed.
+    async fn health_check(&self) -> Result<()> {
+        let models = list_models(&self.base_url).await?;
+        if models.contains(&self.model) {
+            Ok(())
+        } else {
+            Err(anyhow!("model {} is not installed", self.model))
+        }
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            id: format!("ollama-{}", self.model),
+            name: format!("Ollama ({})", self.model),
+            capabilities: vec![
+                Capability { name: "text-generation".to_string(), score: 70, cost_per_1k_tokens: 0.0, output_cost_per_1k_tokens: 0.0 },
+                Capability { name: "code-editing".to_string(), score: 60, cost_per_1k_tokens: 0.0, output_cost_per_1k_tokens: 0.0 },
+                Capability { name: "complex-reasoning".to_string(), score: 50, cost_per_1k_tokens: 0.0, output_cost_per_1k_tokens: 0.0 },
+                Capability { name: "local-privacy".to_string(), score: 100, cost_per_1k_tokens: 0.0, output_cost_per_1k_tokens: 0.0 },
+            ],
+            latency_ms: 100,
+            privacy_level: crate::PrivacyLevel::Local,
+        }
+    }
+
+    async fn execute(&self, task: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        let client = reqwest::Client::new();
+        let res = client.post(format!("{}/api/generate", self.base_url))
+            .json(&self.generate_body(task, &params, false))
+            .send().await?;
+
+        if res.status().is_success() {
+            let body: serde_json::Value = res.json().await?;
+            Ok(with_usage(body))
+        } else {
+            Err(anyhow!("Ollama failed with status: {}", res.status()))
+        }
+    }
+
+    async fn execute_stream(&self, task: &str, params: serde_json::Value) -> Result<ProviderStream> {
+        let client = reqwest::Client::new();
+        let res = client.post(format!("{}/api/generate", self.base_url))
+            .json(&self.generate_body(task, &params, true))
+            .send().await?;
+
+        if !res.status().is_success() {
+            return Err(anyhow!("Ollama failed with status: {}", res.status()));
+        }
+
+        // Ollama streams newline-delimited JSON objects, the last of which carries `done: true`
+        // along with the prompt/eval token counts.
+        let state = (res, Vec::new(), VecDeque::new(), false);
+        let stream = futures_util::stream::try_unfold(state, |(mut res, mut buffer, mut pending, mut done)| async move {
+            loop {
+                if let Some(event) = pending.pop_front() {
+                    return Ok(Some((event, (res, buffer, pending, done))));
+                }
+                if done {
+                    return Ok(None);
+                }
+                match res.chunk().await? {
+                    Some(chunk) => {
+                        buffer.extend_from_slice(&chunk);
+                        pending.extend(drain_ndjson(&mut buffer, false));
+                    }
+                    None => {
+                        pending.extend(drain_ndjson(&mut buffer, true));
+                        done = true;
+                    }
+                }
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+}
+
+/// Token counts from a response that is `done`.
+fn usage(value: &serde_json::Value) -> Usage {
+    Usage {
+        input_tokens: value["prompt_eval_count"].as_u64().unwrap_or(0),
+        output_tokens: value["eval_count"].as_u64().unwrap_or(0),
+        ..Usage::default()
+    }
+}
+
+/// Adds the `usage` the router prices a response by to Ollama's own body.
+fn with_usage(mut body: serde_json::Value) -> serde_json::Value {
+    body["usage"] = serde_json::json!(usage(&body));
+    body
+}
+
+/// Events for every complete line in `buffer`, leaving a partial line for the next chunk.
+/// At the end of the body (`eof`) an unterminated last line is parsed too.
+fn drain_ndjson(buffer: &mut Vec<u8>, eof: bool) -> Vec<ProviderEvent> {
+    let mut events = Vec::new();
+    while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+        let line: Vec<u8> = buffer.drain(..=pos).collect();
+        if let Ok(value) = serde_json::from_slice::<serde_json::Value>(&line) {
+            events.extend(ndjson_events(&value));
+        }
+    }
+    if eof && !buffer.is_empty() {
+        if let Ok(value) = serde_json::from_slice::<serde_json::Value>(buffer) {
+            events.extend(ndjson_events(&value));
+        }
+        buffer.clear();
+    }
+    events
+}
+
+fn ndjson_events(value: &serde_json::Value) -> Vec<ProviderEvent> {
+    let mut events = Vec::new();
+    if let Some(text) = value["response"].as_str().filter(|t| !t.is_empty()) {
+        events.push(ProviderEvent::Token { text: text.to_string() });
+    }
+    if value["done"].as_bool().unwrap_or(false) {
+        events.push(ProviderEvent::Usage { usage: usage(value) });
+        events.push(ProviderEvent::Done {
+            stop_reason: value["done_reason"].as_str().map(str::to_string),
+        });
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ndjson_chunks_split_mid_line_become_events() {
+        let body = concat!(
+            "{\"model\":\"llama3\",\"response\":\"Hel\",\"done\":false}\n",
+            "{\"model\":\"llama3\",\"response\":\"lo\",\"done\":false}\n",
+            "{\"model\":\"llama3\",\"response\":\"\",\"done\":true,\"done_reason\":\"stop\",\"prompt_eval_count\":7,\"eval_count\":2}",
+        );
+        let mut buffer = Vec::new();
+        let mut events = Vec::new();
+        for chunk in body.as_bytes().chunks(17) {
+            buffer.extend_from_slice(chunk);
+            events.extend(drain_ndjson(&mut buffer, false));
+        }
+        events.extend(drain_ndjson(&mut buffer, true));
+
+        assert_eq!(events, [
+            ProviderEvent::Token { text: "Hel".to_string() },
+            ProviderEvent::Token { text: "lo".to_string() },
+            ProviderEvent::Usage { usage: Usage { input_tokens: 7, output_tokens: 2, ..Usage::default() } },
+            ProviderEvent::Done { stop_reason: Some("stop".to_string()) },
+        ]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_response_carries_usage_from_eval_counts() {
+        let body = serde_json::json!({
+            "model": "llama3", "response": "Hello", "done": true, "prompt_eval_count": 26, "eval_count": 298,
+        });
+        let body = with_usage(body);
+        assert_eq!(body["response"], "Hello");
+        assert_eq!(Usage::from_response(&body), Some(Usage { input_tokens: 26, output_tokens: 298, ..Usage::default() }));
+    }
+}
@@ -1,31 +1,36 @@
-use crate::{Provider, ProviderInfo, Capability};
-use async_trait::async_trait;
-use anyhow::{Result, anyhow};
-use serde_json::json;
-
-pub struct OpenAIProvider {
-    pub model: String,
-    pub api_key: String,
-}
-
-#[async_trait]
-impl Provider for OpenAIProvider {
-    fn info(&self) -> ProviderInfo {
-        ProviderInfo {
-            id: format!("openai-{}", self.model),
-            name: format!("OpenAI ({})", self.model),
-            capabilities: vec![
-                Capability { name: "text-generation".to_string(), score: 95, cost_per_1k_tokens: 0.01 },
-                Capability { name: "code-editing".to_string(), score: 90, cost_per_1k_tokens: 0.01 },
-                Capability { name: "complex-reasoning".to_string(), score: 98, cost_per_1k_tokens: 0.03 },
-            ],
-            latency_ms: 1000,
-            privacy_level: crate::PrivacyLevel::Cloud,
-        }
-    }
-
-    async fn execute(&self, task: &str, _params: serde_json::Value) -> Result<serde_json::Value> {
-        // Full implementation would call OpenAI API
-        Ok(json!({"response": "OpenAI mock response", "model": self.model}))
-    }
-}
+use crate::{Provider, ProviderInfo, Capability};
+use async_trait::async_trait;
+use anyhow::{Result, anyhow};
+
+pub struct OpenAIProvider {
+    pub model: String,
+    pub api_key: String,
+}
+
+#[async_trait]
+impl Provider for OpenAIProvider {
+    fn model(&self) -> Option<String> {
+        Some(self.model.clone())
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            id: format!("openai-{}", self.model),
+            name: format!("OpenAI ({})", self.model),
+            capabilities: vec![
+                Capability { name: "text-generation".to_string(), score: 95, cost_per_1k_tokens: 0.01, output_cost_per_1k_tokens: 0.03 },
+                Capability { name: "code-editing".to_string(), score: 90, cost_per_1k_tokens: 0.01, output_cost_per_1k_tokens: 0.03 },
+                Capability { name: "complex-reasoning".to_string(), score: 98, cost_per_1k_tokens: 0.03, output_cost_per_1k_tokens: 0.06 },
+                Capability { name: "long-context".to_string(), score: 85, cost_per_1k_tokens: 0.01, output_cost_per_1k_tokens: 0.03 },
+            ],
+            latency_ms: 1000,
+            privacy_level: crate::PrivacyLevel::Cloud,
+        }
+    }
+
+    /// Not implemented yet. Fails rather than answering with placeholder text, so a chain
+    /// that ranks this provider first falls back to one that can really serve the call.
+    async fn execute(&self, _task: &str, _params: serde_json::Value) -> Result<serde_json::Value> {
+        Err(anyhow!("The OpenAI adapter is not implemented (model {})", self.model))
+    }
+}
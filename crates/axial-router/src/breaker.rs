@@ -0,0 +1,157 @@
+use serde::{Serialize, Deserialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum BreakerState {
+    /// Calls flow normally.
+    Closed,
+    /// Too many consecutive failures; the provider is skipped until the cooldown elapses.
+    Open,
+    /// Cooldown elapsed; a single trial call either closes or re-opens the breaker. Other
+    /// calls are turned away while it is in flight.
+    HalfOpen,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakerSnapshot {
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+    /// Milliseconds until an open breaker half-opens.
+    pub retry_in_ms: Option<u64>,
+}
+
+struct BreakerInner {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// When the half-open trial started. A trial that never reports back frees the slot
+    /// after another cooldown.
+    probe_started_at: Option<Instant>,
+}
+
+/// Per-provider circuit breaker. Interior mutability lets the router record outcomes via `&self`.
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    inner: Mutex<BreakerInner>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(DEFAULT_FAILURE_THRESHOLD, DEFAULT_COOLDOWN)
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            inner: Mutex::new(BreakerInner { consecutive_failures: 0, opened_at: None, probe_started_at: None }),
+        }
+    }
+
+    pub fn state(&self) -> BreakerState {
+        let inner = self.inner.lock().unwrap();
+        match inner.opened_at {
+            None => BreakerState::Closed,
+            Some(at) if at.elapsed() < self.cooldown => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+
+    fn probe_in_flight(&self, inner: &BreakerInner) -> bool {
+        inner.probe_started_at.is_some_and(|at| at.elapsed() < self.cooldown)
+    }
+
+    /// Whether a call would be let through right now. Doesn't take the half-open trial;
+    /// `try_acquire` does.
+    pub fn allows_request(&self) -> bool {
+        match self.state() {
+            BreakerState::Closed => true,
+            BreakerState::Open => false,
+            BreakerState::HalfOpen => !self.probe_in_flight(&self.inner.lock().unwrap()),
+        }
+    }
+
+    /// Lets a call through, claiming the half-open trial when there is one. Callers must
+    /// report the call's outcome with `record_success` or `record_failure`.
+    pub fn try_acquire(&self) -> bool {
+        let state = self.state();
+        let mut inner = self.inner.lock().unwrap();
+        match state {
+            BreakerState::Closed => true,
+            BreakerState::Open => false,
+            BreakerState::HalfOpen if self.probe_in_flight(&inner) => false,
+            BreakerState::HalfOpen => {
+                inner.probe_started_at = Some(Instant::now());
+                true
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+        inner.probe_started_at = None;
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures += 1;
+        inner.probe_started_at = None;
+        // A failed half-open trial re-opens immediately, as does crossing the threshold.
+        if inner.opened_at.is_some() || inner.consecutive_failures >= self.failure_threshold {
+            inner.opened_at = Some(Instant::now());
+        }
+    }
+
+    pub fn snapshot(&self) -> BreakerSnapshot {
+        let state = self.state();
+        let inner = self.inner.lock().unwrap();
+        let retry_in_ms = match (state, inner.opened_at) {
+            (BreakerState::Open, Some(at)) => Some(self.cooldown.saturating_sub(at.elapsed()).as_millis() as u64),
+            _ => None,
+        };
+        BreakerSnapshot {
+            state,
+            consecutive_failures: inner.consecutive_failures,
+            retry_in_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_and_half_opens() {
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(20));
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Open);
+        assert!(!breaker.allows_request());
+
+        std::thread::sleep(Duration::from_millis(25));
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+        // Only one trial at a time.
+        assert!(breaker.try_acquire());
+        assert!(!breaker.allows_request());
+        assert!(!breaker.try_acquire());
+
+        // Failed trial re-opens without waiting for the threshold again.
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Open);
+
+        std::thread::sleep(Duration::from_millis(25));
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        assert_eq!(breaker.snapshot().consecutive_failures, 0);
+    }
+}
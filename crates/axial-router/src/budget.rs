@@ -0,0 +1,259 @@
+use crate::learning::ProviderOutcome;
+use axial_core::Profile;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+
+/// Output tokens assumed for a call when the caller doesn't cap `max_tokens`.
+pub const DEFAULT_OUTPUT_TOKENS: u64 = 1024;
+
+/// Rough pre-call token estimate (~4 bytes per token for English text and code).
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.len() as u64).div_ceil(4)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetScope {
+    Run,
+    Plan,
+    /// Everything spent since midnight UTC under the same profile, or by calls without one.
+    Daily,
+}
+
+impl std::str::FromStr for BudgetScope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "run" => Ok(BudgetScope::Run),
+            "plan" => Ok(BudgetScope::Plan),
+            "daily" | "day" => Ok(BudgetScope::Daily),
+            other => Err(anyhow::anyhow!("Unknown budget scope '{}' (expected run, plan or daily)", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Budget {
+    pub scope: BudgetScope,
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+}
+
+/// What the router does when the preferred provider would exceed a budget.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverBudget {
+    /// Fall back to the best-ranked provider that still fits.
+    #[default]
+    Downgrade,
+    Refuse,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BudgetPolicy {
+    pub budgets: Vec<Budget>,
+    pub on_exceed: OverBudget,
+}
+
+impl BudgetPolicy {
+    /// Reads `budget.<scope>_usd`, `budget.<scope>_tokens` and `budget.on_exceed` profile constraints.
+    pub fn from_profile(profile: &Profile) -> Self {
+        let mut policy = Self::default();
+        for scope in [BudgetScope::Run, BudgetScope::Plan, BudgetScope::Daily] {
+            let name = scope_name(scope);
+            let usd = profile_constraint(profile, &format!("budget.{}_usd", name)).and_then(|v| v.as_f64());
+            let tokens = profile_constraint(profile, &format!("budget.{}_tokens", name)).and_then(|v| v.as_u64());
+            if usd.is_some() || tokens.is_some() {
+                policy.budgets.push(Budget { scope, max_cost_usd: usd, max_tokens: tokens });
+            }
+        }
+        if let Some(action) = profile_constraint(profile, "budget.on_exceed") {
+            policy.on_exceed = serde_json::from_value(action.clone()).unwrap_or_default();
+        }
+        policy
+    }
+
+    pub fn with(mut self, budget: Budget) -> Self {
+        self.budgets.push(budget);
+        self
+    }
+
+    /// The tightest remaining allowance across every budget that applies to this call.
+    /// `spend` must be current to `now`'s day; see `SpendReport::roll_over`.
+    pub fn headroom(&self, spend: &SpendReport, profile: Option<&str>, run_id: Option<&str>, plan_id: Option<&str>) -> Headroom {
+        let mut headroom = Headroom::default();
+        for budget in &self.budgets {
+            let spent = match budget.scope {
+                BudgetScope::Run => run_id.and_then(|id| spend.by_run.get(id)).cloned().unwrap_or_default(),
+                BudgetScope::Plan => plan_id.and_then(|id| spend.by_plan.get(id)).cloned().unwrap_or_default(),
+                BudgetScope::Daily => spend.today_by_profile.get(profile.unwrap_or_default()).cloned().unwrap_or_default(),
+            };
+            if let Some(max) = budget.max_cost_usd {
+                let left = (max - spent.cost_usd).max(0.0);
+                headroom.cost_usd = Some(headroom.cost_usd.map_or(left, |c| c.min(left)));
+            }
+            if let Some(max) = budget.max_tokens {
+                let left = max.saturating_sub(spent.tokens);
+                headroom.tokens = Some(headroom.tokens.map_or(left, |t| t.min(left)));
+            }
+        }
+        headroom
+    }
+}
+
+fn scope_name(scope: BudgetScope) -> &'static str {
+    match scope {
+        BudgetScope::Run => "run",
+        BudgetScope::Plan => "plan",
+        BudgetScope::Daily => "daily",
+    }
+}
+
+pub(crate) fn profile_constraint<'a>(profile: &'a Profile, key: &str) -> Option<&'a serde_json::Value> {
+    profile.constraints.iter().find(|c| c.key == key).map(|c| &c.value)
+}
+
+/// Remaining allowance; `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Headroom {
+    pub cost_usd: Option<f64>,
+    pub tokens: Option<u64>,
+}
+
+impl Headroom {
+    pub fn admits(&self, cost_usd: f64, tokens: u64) -> bool {
+        self.cost_usd.is_none_or(|c| cost_usd <= c) && self.tokens.is_none_or(|t| tokens <= t)
+    }
+
+    /// Nothing at all is left, so no call can be made.
+    pub fn is_exhausted(&self) -> bool {
+        self.cost_usd.is_some_and(|c| c <= 0.0) || self.tokens == Some(0)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Spend {
+    pub cost_usd: f64,
+    pub tokens: u64,
+    pub calls: u32,
+}
+
+impl Spend {
+    fn add(&mut self, outcome: &ProviderOutcome) {
+        self.cost_usd += outcome.cost;
+        self.tokens += outcome.tokens;
+        self.calls += 1;
+    }
+}
+
+/// Spend aggregated from the `provider_outcome` entries in the ledger.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpendReport {
+    /// The UTC day `today` covers.
+    pub day: Option<NaiveDate>,
+    pub today: Spend,
+    /// Today's spend per profile; calls made without one are under `""`.
+    pub today_by_profile: HashMap<String, Spend>,
+    pub total: Spend,
+    pub by_provider: HashMap<String, Spend>,
+    pub by_run: HashMap<String, Spend>,
+    pub by_plan: HashMap<String, Spend>,
+}
+
+impl SpendReport {
+    pub fn from_outcomes(records: &[(DateTime<Utc>, ProviderOutcome)], now: DateTime<Utc>) -> Self {
+        let mut report = Self::default();
+        report.roll_over(now);
+        for (timestamp, outcome) in records {
+            report.record(*timestamp, outcome);
+        }
+        report
+    }
+
+    /// Starts a new `today` once `now` is past the day it covers.
+    pub fn roll_over(&mut self, now: DateTime<Utc>) {
+        if self.day.is_none_or(|day| now.date_naive() > day) {
+            self.day = Some(now.date_naive());
+            self.today = Spend::default();
+            self.today_by_profile.clear();
+        }
+    }
+
+    /// Adds one outcome, so a long-running process can keep the report current without
+    /// reading the ledger again.
+    pub fn record(&mut self, timestamp: DateTime<Utc>, outcome: &ProviderOutcome) {
+        self.roll_over(timestamp);
+        self.total.add(outcome);
+        if Some(timestamp.date_naive()) == self.day {
+            self.today.add(outcome);
+            self.today_by_profile.entry(outcome.profile.clone().unwrap_or_default()).or_default().add(outcome);
+        }
+        self.by_provider.entry(outcome.provider_id.clone()).or_default().add(outcome);
+        if let Some(run_id) = &outcome.run_id {
+            self.by_run.entry(run_id.clone()).or_default().add(outcome);
+        }
+        if let Some(plan_id) = &outcome.plan_id {
+            self.by_plan.entry(plan_id.clone()).or_default().add(outcome);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axial_core::Constraint;
+
+    fn spent(cost: f64, run_id: &str, profile: Option<&str>) -> ProviderOutcome {
+        ProviderOutcome {
+            provider_id: "anthropic-claude".to_string(),
+            task_type: "coding".to_string(),
+            success: true,
+            latency_ms: 900,
+            cost,
+            tokens: 1000,
+            invariants_passed: None,
+            run_id: Some(run_id.to_string()),
+            plan_id: None,
+            profile: profile.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_headroom_is_tightest_applicable_budget() {
+        let profile = Profile {
+            name: "work".to_string(),
+            constraints: vec![
+                Constraint { key: "budget.run_usd".to_string(), value: serde_json::json!(5.0) },
+                Constraint { key: "budget.daily_usd".to_string(), value: serde_json::json!(50.0) },
+                Constraint { key: "budget.on_exceed".to_string(), value: serde_json::json!("refuse") },
+            ],
+            preferred_tools: vec![],
+        };
+        let policy = BudgetPolicy::from_profile(&profile);
+        assert_eq!(policy.on_exceed, OverBudget::Refuse);
+
+        let now = Utc::now();
+        let yesterday = now - chrono::Duration::days(1);
+        let mut report = SpendReport::from_outcomes(&[
+            (yesterday, spent(40.0, "old", Some("work"))),
+            (now, spent(46.0, "other", Some("work"))),
+            (now, spent(30.0, "personal", Some("home"))),
+        ], now);
+        report.record(now, &spent(1.5, "current", Some("work")));
+
+        // Run budget leaves 3.5, daily leaves 2.5; yesterday's spend and other profiles' don't count.
+        let headroom = policy.headroom(&report, Some("work"), Some("current"), None);
+        assert!((headroom.cost_usd.unwrap() - 2.5).abs() < 1e-9);
+        assert!(headroom.admits(2.0, 10_000));
+        assert!(!headroom.admits(3.0, 10));
+        assert!(!headroom.is_exhausted());
+
+        report.roll_over(now + chrono::Duration::days(1));
+        assert_eq!(report.today.calls, 0);
+        assert!((policy.headroom(&report, Some("work"), Some("current"), None).cost_usd.unwrap() - 3.5).abs() < 1e-9);
+    }
+}
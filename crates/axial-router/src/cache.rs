@@ -0,0 +1,220 @@
+use anyhow::Result;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Ledger `event` tag for responses served from the cache.
+pub const CACHE_HIT_EVENT: &str = "provider_cache_hit";
+
+/// Node param that opts a single call out of the cache: `"response_cache": false`.
+pub const OPT_OUT_PARAM: &str = "response_cache";
+
+pub const DEFAULT_TTL_SECS: i64 = 24 * 60 * 60;
+
+/// Params that change how a response is delivered but not what it says.
+const IGNORED_PARAMS: &[&str] = &["stream", "cache", OPT_OUT_PARAM];
+
+/// Line endings unified, trailing whitespace stripped from each line, and the whole prompt
+/// trimmed, so cosmetic edits don't defeat the cache.
+pub fn normalize_prompt(prompt: &str) -> String {
+    prompt.replace("\r\n", "\n")
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Serializes `value` with object keys sorted at every level.
+fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<_> = map.keys().collect();
+            keys.sort();
+            let fields: Vec<String> = keys.into_iter()
+                .map(|k| format!("{}:{}", Value::String(k.clone()), canonical_json(&map[k])))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => format!("[{}]", items.iter().map(canonical_json).collect::<Vec<_>>().join(",")),
+        other => other.to_string(),
+    }
+}
+
+fn sha256_hex(data: &str) -> String {
+    format!("{:x}", Sha256::digest(data.as_bytes()))
+}
+
+/// Whether the call params allow the cache to be used.
+pub fn enabled_for(params: &Value) -> bool {
+    params.get(OPT_OUT_PARAM).and_then(|v| v.as_bool()) != Some(false)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CacheKey {
+    pub provider_id: String,
+    pub model: String,
+    pub prompt_hash: String,
+    pub params_hash: String,
+}
+
+impl CacheKey {
+    pub fn new(provider_id: &str, model: &str, prompt: &str, params: &Value) -> Self {
+        let mut params = params.clone();
+        if let Some(map) = params.as_object_mut() {
+            for key in IGNORED_PARAMS {
+                map.remove(*key);
+            }
+        }
+        Self {
+            provider_id: provider_id.to_string(),
+            model: model.to_string(),
+            prompt_hash: sha256_hex(&normalize_prompt(prompt)),
+            params_hash: sha256_hex(&canonical_json(&params)),
+        }
+    }
+
+    pub fn digest(&self) -> String {
+        sha256_hex(&format!("{}\n{}\n{}\n{}", self.provider_id, self.model, self.prompt_hash, self.params_hash))
+    }
+}
+
+/// A cached response served in place of a live call. Recorded in the ledger by the caller.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheHit {
+    pub key: String,
+    pub provider_id: String,
+    pub model: String,
+    pub cached_at: DateTime<Utc>,
+    pub age_secs: i64,
+    #[serde(default)]
+    pub run_id: Option<String>,
+    #[serde(default)]
+    pub plan_id: Option<String>,
+}
+
+impl CacheHit {
+    pub fn to_ledger_payload(&self) -> Value {
+        let mut payload = serde_json::to_value(self).unwrap_or_else(|_| json!({}));
+        payload["event"] = json!(CACHE_HIT_EVENT);
+        payload
+    }
+}
+
+/// Provider responses in SQLite, expiring after a fixed TTL.
+pub struct ResponseCache {
+    pool: SqlitePool,
+    ttl_secs: i64,
+}
+
+impl ResponseCache {
+    pub async fn open(path: &Path) -> Result<Self> {
+        let pool = SqlitePool::connect_with(
+            SqliteConnectOptions::from_str(&format!("sqlite:{}", path.to_string_lossy()))?
+                .create_if_missing(true)
+        ).await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS response_cache (
+                key TEXT PRIMARY KEY,
+                provider_id TEXT NOT NULL,
+                model TEXT NOT NULL,
+                response TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL,
+                hits INTEGER NOT NULL DEFAULT 0
+            )"
+        ).execute(&pool).await?;
+
+        Ok(Self { pool, ttl_secs: DEFAULT_TTL_SECS })
+    }
+
+    pub fn with_ttl_secs(mut self, ttl_secs: i64) -> Self {
+        self.ttl_secs = ttl_secs;
+        self
+    }
+
+    pub async fn get(&self, key: &CacheKey) -> Result<Option<(Value, CacheHit)>> {
+        let digest = key.digest();
+        let now = Utc::now().timestamp();
+        let row: Option<(String, i64)> = sqlx::query_as(
+            "SELECT response, created_at FROM response_cache WHERE key = ? AND expires_at > ?"
+        )
+            .bind(&digest)
+            .bind(now)
+            .fetch_optional(&self.pool).await?;
+
+        let Some((response, created_at)) = row else { return Ok(None) };
+        sqlx::query("UPDATE response_cache SET hits = hits + 1 WHERE key = ?")
+            .bind(&digest)
+            .execute(&self.pool).await?;
+
+        let hit = CacheHit {
+            key: digest,
+            provider_id: key.provider_id.clone(),
+            model: key.model.clone(),
+            cached_at: Utc.timestamp_opt(created_at, 0).single().unwrap_or_else(Utc::now),
+            age_secs: now - created_at,
+            run_id: None,
+            plan_id: None,
+        };
+        Ok(Some((serde_json::from_str(&response)?, hit)))
+    }
+
+    pub async fn put(&self, key: &CacheKey, response: &Value) -> Result<()> {
+        let now = Utc::now().timestamp();
+        sqlx::query(
+            "INSERT OR REPLACE INTO response_cache (key, provider_id, model, response, created_at, expires_at, hits)
+             VALUES (?, ?, ?, ?, ?, ?, 0)"
+        )
+            .bind(key.digest())
+            .bind(&key.provider_id)
+            .bind(&key.model)
+            .bind(serde_json::to_string(response)?)
+            .bind(now)
+            .bind(now + self.ttl_secs)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Deletes expired entries, returning how many were removed.
+    pub async fn purge_expired(&self) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM response_cache WHERE expires_at <= ?")
+            .bind(Utc::now().timestamp())
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cache_key_normalization_and_round_trip() {
+        let a = CacheKey::new("ollama-llama3", "llama3", "Fix the bug  \r\n", &json!({ "temperature": 0, "system": "s", "stream": true }));
+        let b = CacheKey::new("ollama-llama3", "llama3", "Fix the bug", &json!({ "system": "s", "temperature": 0 }));
+        assert_eq!(a.digest(), b.digest());
+        assert_ne!(a.digest(), CacheKey::new("ollama-llama3", "llama3", "Fix the bug", &json!({ "temperature": 1 })).digest());
+        assert!(!enabled_for(&json!({ "response_cache": false })));
+
+        let path = std::env::temp_dir().join(format!("axial-cache-test-{}.db", uuid::Uuid::new_v4()));
+        let cache = ResponseCache::open(&path).await.unwrap();
+        assert!(cache.get(&a).await.unwrap().is_none());
+        cache.put(&a, &json!({ "response": "done" })).await.unwrap();
+        let (response, hit) = cache.get(&b).await.unwrap().unwrap();
+        assert_eq!(response["response"], "done");
+        assert_eq!(hit.provider_id, "ollama-llama3");
+
+        let expired = ResponseCache::open(&path).await.unwrap().with_ttl_secs(-1);
+        expired.put(&a, &json!({ "response": "stale" })).await.unwrap();
+        assert!(expired.get(&a).await.unwrap().is_none());
+        assert_eq!(expired.purge_expired().await.unwrap(), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+}
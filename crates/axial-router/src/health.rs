@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use std::time::Duration;
+
+pub const DEFAULT_PROBE_INTERVAL_SECS: u64 = 30;
+
+/// A probe that takes longer than this counts as a failure.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Weight given to the newest call latency in the moving average.
+const LATENCY_SMOOTHING: f64 = 0.3;
+
+/// Reachability and observed latency for one provider. A provider that has never been
+/// probed is assumed healthy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderHealth {
+    pub healthy: bool,
+    pub checked_at: Option<DateTime<Utc>>,
+    pub probe_latency_ms: Option<u64>,
+    /// Moving average over successful calls.
+    pub call_latency_ms: Option<f64>,
+    pub last_error: Option<String>,
+    pub consecutive_failures: u32,
+}
+
+impl Default for ProviderHealth {
+    fn default() -> Self {
+        Self {
+            healthy: true,
+            checked_at: None,
+            probe_latency_ms: None,
+            call_latency_ms: None,
+            last_error: None,
+            consecutive_failures: 0,
+        }
+    }
+}
+
+impl ProviderHealth {
+    pub fn record_probe(&mut self, result: Result<(), String>, latency_ms: u64) {
+        self.checked_at = Some(Utc::now());
+        match result {
+            Ok(()) => {
+                self.healthy = true;
+                self.probe_latency_ms = Some(latency_ms);
+                self.last_error = None;
+                self.consecutive_failures = 0;
+            }
+            Err(e) => {
+                self.healthy = false;
+                self.last_error = Some(e);
+                self.consecutive_failures += 1;
+            }
+        }
+    }
+
+    pub fn record_call_latency(&mut self, latency_ms: u64) {
+        let latency = latency_ms as f64;
+        self.call_latency_ms = Some(match self.call_latency_ms {
+            Some(avg) => avg + LATENCY_SMOOTHING * (latency - avg),
+            None => latency,
+        });
+    }
+
+    /// Best measured latency: real calls when there have been any, otherwise the probe.
+    pub fn latency_ms(&self) -> Option<f64> {
+        self.call_latency_ms.or(self.probe_latency_ms.map(|ms| ms as f64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe_failure_marks_unhealthy_and_calls_override_probe_latency() {
+        let mut health = ProviderHealth::default();
+        assert!(health.healthy);
+
+        health.record_probe(Ok(()), 12);
+        assert_eq!(health.latency_ms(), Some(12.0));
+
+        health.record_probe(Err("connection refused".to_string()), 5000);
+        assert!(!health.healthy);
+        assert_eq!(health.consecutive_failures, 1);
+
+        health.record_call_latency(800);
+        health.record_call_latency(400);
+        assert_eq!(health.latency_ms(), Some(680.0));
+    }
+}
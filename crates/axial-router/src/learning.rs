@@ -0,0 +1,254 @@
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Ledger `event` tag for recorded provider outcomes.
+pub const OUTCOME_EVENT: &str = "provider_outcome";
+
+/// Ledger `event` tag for a plan's invariant verdict.
+pub const INVARIANTS_EVENT: &str = "invariants_checked";
+
+/// Number of observations at which the empirical score and the configured prior carry equal weight.
+pub const PRIOR_STRENGTH: f64 = 10.0;
+
+/// The result of one provider call, as written to and read back from the ledger.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderOutcome {
+    pub provider_id: String,
+    pub task_type: String,
+    pub success: bool,
+    pub latency_ms: u64,
+    #[serde(default)]
+    pub cost: f64,
+    #[serde(default)]
+    pub tokens: u64,
+    /// Whether the invariants checked after this call passed, if any were checked.
+    #[serde(default)]
+    pub invariants_passed: Option<bool>,
+    #[serde(default)]
+    pub run_id: Option<String>,
+    #[serde(default)]
+    pub plan_id: Option<String>,
+    /// Profile the call was made under, whose daily budget it counts against.
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+impl ProviderOutcome {
+    pub fn to_ledger_payload(&self) -> serde_json::Value {
+        let mut payload = serde_json::to_value(self).unwrap_or_else(|_| json!({}));
+        payload["event"] = json!(OUTCOME_EVENT);
+        payload
+    }
+
+    pub fn from_ledger_payload(payload: &serde_json::Value) -> Option<Self> {
+        if payload.get("event").and_then(|e| e.as_str()) != Some(OUTCOME_EVENT) {
+            return None;
+        }
+        serde_json::from_value(payload.clone()).ok()
+    }
+}
+
+/// Whether a plan's invariants held once its run was checked. The verdict is credited to
+/// every successful provider call recorded under the same plan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvariantVerdict {
+    pub plan_id: String,
+    pub passed: bool,
+}
+
+impl InvariantVerdict {
+    pub fn to_ledger_payload(&self) -> serde_json::Value {
+        let mut payload = serde_json::to_value(self).unwrap_or_else(|_| json!({}));
+        payload["event"] = json!(INVARIANTS_EVENT);
+        payload
+    }
+
+    pub fn from_ledger_payload(payload: &serde_json::Value) -> Option<Self> {
+        if payload.get("event").and_then(|e| e.as_str()) != Some(INVARIANTS_EVENT) {
+            return None;
+        }
+        serde_json::from_value(payload.clone()).ok()
+    }
+}
+
+/// Fills in `invariants_passed` from the verdicts of the outcomes' plans. The last verdict
+/// for a plan wins; outcomes that already carry a result keep it.
+pub fn credit_invariants(outcomes: &mut [ProviderOutcome], verdicts: &[InvariantVerdict]) {
+    let by_plan: HashMap<&str, bool> = verdicts.iter().map(|v| (v.plan_id.as_str(), v.passed)).collect();
+    for outcome in outcomes.iter_mut().filter(|o| o.success && o.invariants_passed.is_none()) {
+        if let Some(passed) = outcome.plan_id.as_deref().and_then(|plan| by_plan.get(plan)) {
+            outcome.invariants_passed = Some(*passed);
+        }
+    }
+}
+
+/// Aggregated observations for a provider, optionally narrowed to one task type.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ObservedStats {
+    pub samples: u32,
+    pub successes: u32,
+    pub total_latency_ms: u64,
+    pub total_cost: f64,
+    #[serde(default)]
+    pub total_tokens: u64,
+    pub invariant_checks: u32,
+    pub invariant_passes: u32,
+}
+
+impl ObservedStats {
+    fn record(&mut self, outcome: &ProviderOutcome) {
+        self.samples += 1;
+        if outcome.success {
+            self.successes += 1;
+        }
+        self.total_latency_ms += outcome.latency_ms;
+        self.total_cost += outcome.cost;
+        self.total_tokens += outcome.tokens;
+        if let Some(passed) = outcome.invariants_passed {
+            self.invariant_checks += 1;
+            if passed {
+                self.invariant_passes += 1;
+            }
+        }
+    }
+
+    pub fn success_rate(&self) -> f64 {
+        self.successes as f64 / self.samples.max(1) as f64
+    }
+
+    pub fn avg_latency_ms(&self) -> f64 {
+        self.total_latency_ms as f64 / self.samples.max(1) as f64
+    }
+
+    /// What the provider really charged per 1k tokens, once it has reported usage.
+    pub fn cost_per_1k(&self) -> Option<f64> {
+        (self.total_tokens > 0).then(|| self.total_cost / self.total_tokens as f64 * 1000.0)
+    }
+
+    pub fn invariant_pass_rate(&self) -> Option<f64> {
+        if self.invariant_checks == 0 {
+            None
+        } else {
+            Some(self.invariant_passes as f64 / self.invariant_checks as f64)
+        }
+    }
+
+    /// Empirical quality on the same 1-100 scale as `Capability::score`.
+    pub fn observed_score(&self) -> f64 {
+        match self.invariant_pass_rate() {
+            Some(pass_rate) => (self.success_rate() * 0.6 + pass_rate * 0.4) * 100.0,
+            None => self.success_rate() * 100.0,
+        }
+    }
+
+    /// Share of the blended score that comes from observations rather than the prior.
+    pub fn confidence(&self) -> f64 {
+        self.samples as f64 / (self.samples as f64 + PRIOR_STRENGTH)
+    }
+}
+
+/// Historical outcomes per provider and per (provider, task type).
+#[derive(Debug, Clone, Default)]
+pub struct LearnedScores {
+    by_provider: HashMap<String, ObservedStats>,
+    by_task: HashMap<(String, String), ObservedStats>,
+}
+
+impl LearnedScores {
+    pub fn from_outcomes<'a>(outcomes: impl IntoIterator<Item = &'a ProviderOutcome>) -> Self {
+        let mut scores = Self::default();
+        for outcome in outcomes {
+            scores.record(outcome);
+        }
+        scores
+    }
+
+    pub fn record(&mut self, outcome: &ProviderOutcome) {
+        self.by_provider.entry(outcome.provider_id.clone()).or_default().record(outcome);
+        self.by_task
+            .entry((outcome.provider_id.clone(), outcome.task_type.clone()))
+            .or_default()
+            .record(outcome);
+    }
+
+    /// Task-specific stats when a task type is given and has history, otherwise the provider-wide stats.
+    pub fn stats(&self, provider_id: &str, task_type: Option<&str>) -> Option<&ObservedStats> {
+        task_type
+            .and_then(|t| self.by_task.get(&(provider_id.to_string(), t.to_string())))
+            .or_else(|| self.by_provider.get(provider_id))
+    }
+
+    /// Blends a configured capability score with the observed score, weighted by sample count.
+    pub fn blend_score(&self, provider_id: &str, task_type: Option<&str>, prior: f64) -> f64 {
+        match self.stats(provider_id, task_type) {
+            Some(stats) => {
+                let w = stats.confidence();
+                prior * (1.0 - w) + stats.observed_score() * w
+            }
+            None => prior,
+        }
+    }
+
+    pub fn blend_cost(&self, provider_id: &str, task_type: Option<&str>, prior_per_1k: f64) -> f64 {
+        match self.stats(provider_id, task_type).and_then(|stats| Some((stats.confidence(), stats.cost_per_1k()?))) {
+            Some((w, observed)) => prior_per_1k * (1.0 - w) + observed * w,
+            None => prior_per_1k,
+        }
+    }
+
+    pub fn blend_latency(&self, provider_id: &str, task_type: Option<&str>, prior_ms: f64) -> f64 {
+        match self.stats(provider_id, task_type) {
+            Some(stats) => {
+                let w = stats.confidence();
+                prior_ms * (1.0 - w) + stats.avg_latency_ms() * w
+            }
+            None => prior_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(success: bool, task_type: &str) -> ProviderOutcome {
+        ProviderOutcome {
+            provider_id: "openai-gpt-4o".to_string(),
+            task_type: task_type.to_string(),
+            success,
+            latency_ms: 2000,
+            cost: 0.01,
+            tokens: 500,
+            invariants_passed: None,
+            run_id: None,
+            plan_id: None,
+            profile: None,
+        }
+    }
+
+    #[test]
+    fn test_observations_pull_prior_toward_reality() {
+        let outcomes: Vec<_> = (0..30).map(|i| outcome(i % 2 == 0, "coding")).collect();
+        let scores = LearnedScores::from_outcomes(&outcomes);
+
+        let blended = scores.blend_score("openai-gpt-4o", Some("coding"), 95.0);
+        // 30 samples at 50% success against a prior of 95 with strength 10.
+        assert!((blended - 61.25).abs() < 1e-9);
+        assert_eq!(scores.blend_score("unknown", Some("coding"), 70.0), 70.0);
+
+        let payload = outcomes[0].to_ledger_payload();
+        assert_eq!(payload["event"], OUTCOME_EVENT);
+        assert!(ProviderOutcome::from_ledger_payload(&payload).is_some());
+
+        // 500 tokens for $0.01 each time is $0.02 per 1k, pulled in from the prior.
+        let cost = scores.blend_cost("openai-gpt-4o", Some("coding"), 0.01);
+        assert!((cost - (0.01 * 0.25 + 0.02 * 0.75)).abs() < 1e-9);
+
+        let mut planned: Vec<_> = outcomes.iter().take(2).cloned().map(|mut o| { o.plan_id = Some("p1".to_string()); o }).collect();
+        let verdict = InvariantVerdict::from_ledger_payload(&InvariantVerdict { plan_id: "p1".to_string(), passed: false }.to_ledger_payload()).unwrap();
+        credit_invariants(&mut planned, &[verdict]);
+        // Only the call that succeeded is held to the plan's invariants.
+        assert_eq!(planned.iter().map(|o| o.invariants_passed).collect::<Vec<_>>(), [Some(false), None]);
+    }
+}
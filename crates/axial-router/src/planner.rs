@@ -0,0 +1,321 @@
+use crate::budget::{Headroom, OverBudget};
+use crate::learning::ProviderOutcome;
+use crate::privacy::{PrivacyDecision, PrivacyPolicy};
+use axial_core::{ApprovalGate, Invariant, PlanPacket, TaskEdge, TaskGraph, TaskNode};
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Task types the executor knows how to run, with a one-line description for the planner prompt.
+pub const TASK_TYPES: &[(&str, &str)] = &[
+    ("research", "Read code, docs or the web and summarise findings; makes no edits"),
+    ("coding", "Edit files in the workspace, optionally through one of the tool adapters"),
+    ("verification", "Run builds, tests or scanners to check the result of earlier steps"),
+    ("nlp", "Write or transform prose: docs, changelogs, commit messages, summaries"),
+];
+
+/// Invariant `check_type`s the executor can enforce after a step.
+pub const INVARIANT_CATALOG: &[(&str, &str)] = &[
+    ("build-pass", "The project builds without errors"),
+    ("test-pass", "The project's test suite passes"),
+    ("lint-clean", "Clippy and eslint report no new warnings"),
+    ("no-vulnerabilities", "Semgrep, osv-scanner and cargo audit report no new security findings"),
+    ("no-secrets", "The built-in scanner, gitleaks and trufflehog find no secrets in the change"),
+];
+
+/// Attempts made to get a valid plan out of the provider after the first one.
+pub const PLAN_RETRIES: usize = 1;
+
+/// Output cap for a planner call, also what its budget estimate assumes.
+pub const MAX_PLAN_TOKENS: u64 = 4096;
+
+/// `task_type` recorded on the provider outcomes of planner calls.
+pub const PLAN_TASK_TYPE: &str = "planning";
+
+pub const SYSTEM_PROMPT: &str = "You are the planner for AXIAL, a local-first agent orchestrator. \
+Break the user's goal into a small directed acyclic graph of steps. Reply with a single JSON object \
+matching the given PlanPacket schema and nothing else.";
+
+/// What the planner knows about the environment it is planning for.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlanContext {
+    /// Repository summary from `PerceptionEngine`: files and their symbols.
+    pub repository: Value,
+    /// Names of the tool adapters available to `coding` steps.
+    pub tools: Vec<String>,
+}
+
+/// What a planner call is checked against before it leaves the machine: the same privacy
+/// guard and budget `/execute` applies to a task.
+pub struct PlanGuard<'a> {
+    pub shield: &'a axial_shield::Shield,
+    pub privacy: &'a PrivacyPolicy,
+    pub headroom: Headroom,
+    pub on_exceed: OverBudget,
+}
+
+/// A plan from `Router::decompose` and what the caller should write to its ledger.
+#[derive(Debug, Clone)]
+pub struct GeneratedPlan {
+    pub plan: PlanPacket,
+    /// The privacy check on the planner prompt.
+    pub privacy: PrivacyDecision,
+    /// One per provider call made, priced from the usage it reported. Empty for a cache hit.
+    pub outcomes: Vec<ProviderOutcome>,
+}
+
+pub fn build_prompt(goal: &str, context: &PlanContext) -> String {
+    let task_types: Vec<String> = TASK_TYPES.iter().map(|(name, desc)| format!("- {}: {}", name, desc)).collect();
+    let invariants: Vec<String> = INVARIANT_CATALOG.iter().map(|(name, desc)| format!("- {}: {}", name, desc)).collect();
+    let tools = if context.tools.is_empty() { "none".to_string() } else { context.tools.join(", ") };
+
+    format!(
+        "Goal: {goal}\n\n\
+         Task types (use exactly these for `task_type`):\n{task_types}\n\n\
+         Invariants (use these for `check_type`):\n{invariants}\n\n\
+         Tool adapters available to coding steps (set `params.tool`): {tools}\n\n\
+         Repository context:\n{repository}\n\n\
+         PlanPacket JSON schema:\n{schema}\n\n\
+         Give every node a short kebab-case `id`, put the step's instruction in `params.instruction`, \
+         and add an edge from each step to the steps that depend on it.",
+        goal = goal,
+        task_types = task_types.join("\n"),
+        invariants = invariants.join("\n"),
+        tools = tools,
+        repository = serde_json::to_string_pretty(&context.repository).unwrap_or_default(),
+        schema = axial_core::plan_packet_schema(),
+    )
+}
+
+/// Pulls the first JSON object out of a model reply, tolerating code fences and surrounding prose.
+pub fn extract_json(text: &str) -> Option<Value> {
+    if let Ok(value) = serde_json::from_str::<Value>(text.trim()) {
+        return value.is_object().then_some(value);
+    }
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    if end <= start {
+        return None;
+    }
+    serde_json::from_str(&text[start..=end]).ok()
+}
+
+fn normalize_task_type(raw: &str) -> Option<&'static str> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "research" | "analysis" | "analyze" | "investigate" | "explore" => Some("research"),
+        "coding" | "code" | "edit" | "implement" | "refactor" => Some("coding"),
+        "verification" | "verify" | "test" | "testing" | "check" | "review" => Some("verification"),
+        "nlp" | "text" | "docs" | "documentation" | "write" | "writing" => Some("nlp"),
+        _ => None,
+    }
+}
+
+fn is_known_invariant(check_type: &str) -> bool {
+    INVARIANT_CATALOG.iter().any(|(name, _)| *name == check_type)
+}
+
+fn repair_invariants(raw: Option<&Value>, node_id: &str, repairs: &mut Vec<String>) -> Vec<Invariant> {
+    let mut invariants = Vec::new();
+    for item in raw.and_then(|v| v.as_array()).into_iter().flatten() {
+        let (check_type, id, config) = match item {
+            Value::String(s) => (s.clone(), s.clone(), json!({})),
+            Value::Object(o) => {
+                let check_type = o.get("check_type").or_else(|| o.get("type")).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                let id = o.get("id").and_then(|v| v.as_str()).unwrap_or(&check_type).to_string();
+                (check_type, id, o.get("config").cloned().unwrap_or_else(|| json!({})))
+            }
+            _ => continue,
+        };
+        if is_known_invariant(&check_type) {
+            invariants.push(Invariant { id, check_type, config });
+        } else {
+            repairs.push(format!("dropped unknown invariant '{}' on node '{}'", check_type, node_id));
+        }
+    }
+    invariants
+}
+
+/// True when `to` can already reach `from`, i.e. adding `from -> to` would close a cycle.
+fn creates_cycle(edges: &[TaskEdge], from: &str, to: &str) -> bool {
+    let mut stack = vec![to];
+    let mut seen = HashSet::new();
+    while let Some(node) = stack.pop() {
+        if node == from {
+            return true;
+        }
+        if seen.insert(node) {
+            stack.extend(edges.iter().filter(|e| e.from == node).map(|e| e.to.as_str()));
+        }
+    }
+    false
+}
+
+/// Validates a generated plan and fixes what can be fixed without guessing at intent: missing
+/// ids and metadata, task type aliases, unknown invariants, dangling or duplicate edges, and
+/// edges that would make the graph cyclic. Returns the plan with a note for each repair, or
+/// the reason the plan is unusable.
+pub fn repair_plan(value: Value, goal: &str) -> Result<(PlanPacket, Vec<String>), String> {
+    let mut repairs = Vec::new();
+    let obj = value.as_object().ok_or("plan is not a JSON object")?;
+
+    // Some models return the graph itself, or put nodes/edges at the top level.
+    let graph = match obj.get("graph") {
+        Some(graph) => graph.clone(),
+        None if obj.contains_key("nodes") => {
+            repairs.push("wrapped top-level nodes/edges in a graph".to_string());
+            json!({ "nodes": obj["nodes"], "edges": obj.get("edges").cloned().unwrap_or_else(|| json!([])) })
+        }
+        None => return Err("plan has no graph".to_string()),
+    };
+
+    let id = match obj.get("id").and_then(|v| v.as_str()).and_then(|s| Uuid::parse_str(s).ok()) {
+        Some(id) => id,
+        None => {
+            repairs.push("assigned a new plan id".to_string());
+            Uuid::new_v4()
+        }
+    };
+    let title = match obj.get("title").and_then(|v| v.as_str()).filter(|t| !t.trim().is_empty()) {
+        Some(title) => title.to_string(),
+        None => {
+            repairs.push("added a title".to_string());
+            format!("Plan for: {}", goal)
+        }
+    };
+    let version = match obj.get("version") {
+        Some(Value::String(v)) => v.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => "1.0".to_string(),
+    };
+    let metadata: HashMap<String, String> = obj.get("metadata").and_then(|v| v.as_object()).map(|m| {
+        m.iter().map(|(k, v)| (k.clone(), v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string()))).collect()
+    }).unwrap_or_default();
+
+    let raw_nodes = graph.get("nodes").and_then(|v| v.as_array()).ok_or("graph has no nodes array")?;
+    let mut nodes: Vec<TaskNode> = Vec::new();
+    let mut ids = HashSet::new();
+    // Original id -> final id, so edges still resolve after renaming duplicates.
+    let mut renamed: HashMap<String, String> = HashMap::new();
+
+    for (i, raw) in raw_nodes.iter().enumerate() {
+        let Some(node) = raw.as_object() else {
+            repairs.push(format!("dropped node #{} (not an object)", i + 1));
+            continue;
+        };
+
+        let original_id = node.get("id").and_then(|v| v.as_str()).map(str::trim).filter(|s| !s.is_empty());
+        let mut id = original_id.map(str::to_string).unwrap_or_else(|| {
+            repairs.push(format!("named unnamed node #{}", i + 1));
+            format!("step-{}", i + 1)
+        });
+        if ids.contains(&id) {
+            let base = id.clone();
+            let mut n = 2;
+            while ids.contains(&id) {
+                id = format!("{}-{}", base, n);
+                n += 1;
+            }
+            repairs.push(format!("renamed duplicate node '{}' to '{}'", base, id));
+        }
+        ids.insert(id.clone());
+        if let Some(original) = original_id {
+            renamed.entry(original.to_string()).or_insert_with(|| id.clone());
+        }
+        renamed.entry(id.clone()).or_insert_with(|| id.clone());
+
+        let raw_type = node.get("task_type").or_else(|| node.get("type")).and_then(|v| v.as_str()).unwrap_or("");
+        let task_type = match normalize_task_type(raw_type) {
+            Some(t) => {
+                if t != raw_type {
+                    repairs.push(format!("mapped task type '{}' to '{}' on node '{}'", raw_type, t, id));
+                }
+                t.to_string()
+            }
+            None => {
+                repairs.push(format!("replaced unknown task type '{}' with 'nlp' on node '{}'", raw_type, id));
+                "nlp".to_string()
+            }
+        };
+
+        let params = match node.get("params") {
+            Some(Value::Object(p)) => Value::Object(p.clone()),
+            Some(Value::String(s)) => json!({ "instruction": s }),
+            _ => match node.get("instruction").or_else(|| node.get("description")).and_then(|v| v.as_str()) {
+                Some(s) => json!({ "instruction": s }),
+                None => json!({}),
+            },
+        };
+
+        let invariants = repair_invariants(node.get("invariants"), &id, &mut repairs);
+        let approval_gate = node.get("approval_gate")
+            .filter(|v| !v.is_null())
+            .and_then(|v| serde_json::from_value::<ApprovalGate>(v.clone()).ok());
+
+        nodes.push(TaskNode { id, task_type, params, invariants, approval_gate });
+    }
+
+    if nodes.is_empty() {
+        return Err("plan has no usable nodes".to_string());
+    }
+
+    let mut edges: Vec<TaskEdge> = Vec::new();
+    for raw in graph.get("edges").and_then(|v| v.as_array()).into_iter().flatten() {
+        let endpoint = |key: &str| raw.get(key).and_then(|v| v.as_str()).and_then(|s| renamed.get(s).cloned());
+        let (Some(from), Some(to)) = (endpoint("from"), endpoint("to")) else {
+            repairs.push(format!("dropped edge with unknown endpoints: {}", raw));
+            continue;
+        };
+        if from == to || edges.iter().any(|e| e.from == from && e.to == to) {
+            repairs.push(format!("dropped self-loop or duplicate edge {} -> {}", from, to));
+            continue;
+        }
+        if creates_cycle(&edges, &from, &to) {
+            repairs.push(format!("dropped edge {} -> {} (would create a cycle)", from, to));
+            continue;
+        }
+        let condition = raw.get("condition").and_then(|v| v.as_str()).map(str::to_string);
+        edges.push(TaskEdge { from, to, condition });
+    }
+
+    let plan = PlanPacket { id, title, version, graph: TaskGraph { nodes, edges }, metadata };
+    // Round-trip through the schema-derived type so anything we missed above still fails loudly.
+    let plan: PlanPacket = serde_json::from_value(serde_json::to_value(&plan).map_err(|e| e.to_string())?)
+        .map_err(|e| e.to_string())?;
+    Ok((plan, repairs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repair_plan_fixes_common_model_mistakes() {
+        let reply = r#"Here is the plan:
+```json
+{"title": "", "nodes": [
+  {"id": "read", "type": "analyze", "params": "Find the slow query"},
+  {"task_type": "code", "invariants": ["test-pass", "vibes"]},
+  {"id": "read", "task_type": "verification"}
+], "edges": [
+  {"from": "read", "to": "step-2"},
+  {"from": "step-2", "to": "read"},
+  {"from": "read", "to": "nowhere"}
+]}
+```"#;
+        let value = extract_json(reply).unwrap();
+        let (plan, repairs) = repair_plan(value, "speed up search").unwrap();
+
+        let ids: Vec<_> = plan.graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["read", "step-2", "read-2"]);
+        assert_eq!(plan.graph.nodes[0].task_type, "research");
+        assert_eq!(plan.graph.nodes[0].params["instruction"], "Find the slow query");
+        assert_eq!(plan.graph.nodes[1].task_type, "coding");
+        assert_eq!(plan.graph.nodes[1].invariants.len(), 1);
+        assert_eq!(plan.graph.edges.len(), 1);
+        assert_eq!(plan.title, "Plan for: speed up search");
+        assert!(repairs.iter().any(|r| r.contains("cycle")));
+
+        assert!(repair_plan(json!({ "graph": { "nodes": [] } }), "x").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use crate::budget::profile_constraint;
+use crate::{PrivacyLevel, RouteDecision};
+use axial_core::Profile;
+use axial_shield::{Sensitivity, Shield, Vault};
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+
+/// Ledger `event` tag for privacy routing decisions.
+pub const PRIVACY_EVENT: &str = "privacy_decision";
+
+/// What to do when a prompt bound for a cloud provider contains sensitive content.
+/// Ordered from least to most restrictive.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyAction {
+    /// Send a redacted prompt; cloud candidates are marked `Shielded`.
+    Redact,
+    /// Drop every non-local candidate.
+    ForceLocal,
+    /// Refuse the request.
+    Block,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyPolicy {
+    pub on_secret: PrivacyAction,
+    pub on_pii: PrivacyAction,
+    pub on_proprietary: PrivacyAction,
+}
+
+impl Default for PrivacyPolicy {
+    fn default() -> Self {
+        Self {
+            on_secret: PrivacyAction::ForceLocal,
+            on_pii: PrivacyAction::Redact,
+            on_proprietary: PrivacyAction::ForceLocal,
+        }
+    }
+}
+
+impl PrivacyPolicy {
+    /// Reads `privacy.on_secret`, `privacy.on_pii` and `privacy.on_proprietary` from the
+    /// profile's constraints, keeping the default for anything unset or unrecognised.
+    pub fn from_profile(profile: &Profile) -> Self {
+        let mut policy = Self::default();
+        for (key, slot) in [
+            ("privacy.on_secret", &mut policy.on_secret),
+            ("privacy.on_pii", &mut policy.on_pii),
+            ("privacy.on_proprietary", &mut policy.on_proprietary),
+        ] {
+            if let Some(action) = profile_constraint(profile, key).and_then(|v| serde_json::from_value(v.clone()).ok()) {
+                *slot = action;
+            }
+        }
+        policy
+    }
+
+    pub fn action_for(&self, kind: Sensitivity) -> PrivacyAction {
+        match kind {
+            Sensitivity::Secret => self.on_secret,
+            Sensitivity::Pii => self.on_pii,
+            Sensitivity::Proprietary => self.on_proprietary,
+        }
+    }
+}
+
+/// What the privacy check found and did, as written to the ledger. Holds labels only, never
+/// the sensitive text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyDecision {
+    /// `None` when the prompt was clean or never bound for a cloud provider.
+    pub action: Option<PrivacyAction>,
+    pub kinds: Vec<Sensitivity>,
+    pub labels: Vec<String>,
+    /// Candidates removed from the fallback chain.
+    pub dropped: Vec<String>,
+    pub provider_id: Option<String>,
+    #[serde(default)]
+    pub run_id: Option<String>,
+    #[serde(default)]
+    pub plan_id: Option<String>,
+}
+
+impl PrivacyDecision {
+    pub fn to_ledger_payload(&self) -> serde_json::Value {
+        let mut payload = serde_json::to_value(self).unwrap_or_else(|_| json!({}));
+        payload["event"] = json!(PRIVACY_EVENT);
+        payload
+    }
+}
+
+/// The fallback chain after the privacy check, with the prompt and params each candidate
+/// should receive.
+#[derive(Debug, Clone)]
+pub struct GuardedRoute {
+    pub decisions: Vec<RouteDecision>,
+    pub prompt: String,
+    pub params: Value,
+    /// Set when the policy redacted; goes to candidates marked `shielded`. Holds vault tokens
+    /// rather than placeholders when a vault was given.
+    pub redacted_prompt: Option<String>,
+    /// `params` with every string redacted the same way, set alongside `redacted_prompt`.
+    pub redacted_params: Option<Value>,
+    pub record: PrivacyDecision,
+}
+
+impl GuardedRoute {
+    pub fn prompt_for(&self, decision: &RouteDecision) -> &str {
+        match (&self.redacted_prompt, decision.shielded) {
+            (Some(redacted), true) => redacted,
+            _ => &self.prompt,
+        }
+    }
+
+    pub fn params_for(&self, decision: &RouteDecision) -> &Value {
+        match (&self.redacted_params, decision.shielded) {
+            (Some(redacted), true) => redacted,
+            _ => &self.params,
+        }
+    }
+}
+
+/// Every string in `value`, in document order.
+fn strings<'a>(value: &'a Value, out: &mut Vec<&'a str>) {
+    match value {
+        Value::String(s) => out.push(s),
+        Value::Array(items) => items.iter().for_each(|v| strings(v, out)),
+        Value::Object(map) => map.values().for_each(|v| strings(v, out)),
+        _ => {}
+    }
+}
+
+/// Calls `f` on every string in `value`, in the same order as `strings`.
+fn visit_strings(value: &mut Value, f: &mut impl FnMut(&mut String)) {
+    match value {
+        Value::String(s) => f(s),
+        Value::Array(items) => items.iter_mut().for_each(|v| visit_strings(v, f)),
+        Value::Object(map) => map.values_mut().for_each(|v| visit_strings(v, f)),
+        _ => {}
+    }
+}
+
+/// Classifies `prompt` and every string in `params` (system prompt, messages, tool
+/// definitions) if any candidate is a cloud provider, and applies the strictest action the
+/// policy assigns to what was found. `privacy_of` resolves a candidate's privacy level.
+/// Returns the refusal reason when the request must be blocked.
+pub(crate) fn guard(
+    shield: &Shield,
+    policy: &PrivacyPolicy,
+    decisions: Vec<RouteDecision>,
+    prompt: &str,
+    params: &Value,
+    mut vault: Option<&mut Vault>,
+    privacy_of: impl Fn(&str) -> Option<PrivacyLevel>,
+) -> Result<GuardedRoute, Box<(String, PrivacyDecision)>> {
+    let mut record = PrivacyDecision {
+        action: None,
+        kinds: Vec::new(),
+        labels: Vec::new(),
+        dropped: Vec::new(),
+        provider_id: decisions.first().map(|d| d.provider_id.clone()),
+        run_id: None,
+        plan_id: None,
+    };
+    let is_cloud = |d: &RouteDecision| privacy_of(&d.provider_id) == Some(PrivacyLevel::Cloud);
+
+    let unchanged = |decisions, record| GuardedRoute {
+        decisions,
+        prompt: prompt.to_string(),
+        params: params.clone(),
+        redacted_prompt: None,
+        redacted_params: None,
+        record,
+    };
+    if !decisions.iter().any(is_cloud) {
+        return Ok(unchanged(decisions, record));
+    }
+
+    // The prompt first, then each string in params.
+    let mut texts = vec![prompt];
+    strings(params, &mut texts);
+    let classifications: Vec<_> = texts.iter().map(|text| shield.classify(text)).collect();
+    for classification in &classifications {
+        record.kinds.extend(classification.kinds());
+        record.labels.extend(classification.findings.iter().map(|f| f.detector.clone()));
+    }
+    record.kinds.sort();
+    record.kinds.dedup();
+    record.labels.sort();
+    record.labels.dedup();
+    let action = record.kinds.iter().map(|k| policy.action_for(*k)).max();
+    record.action = action;
+
+    let mut decisions = decisions;
+    let mut redacted_prompt = None;
+    let mut redacted_params = None;
+    match action {
+        None => {}
+        Some(PrivacyAction::Block) => {
+            let reason = format!("prompt contains {:?}; blocked by privacy policy", record.kinds);
+            return Err(Box::new((reason, record)));
+        }
+        Some(PrivacyAction::ForceLocal) => {
+            let (kept, dropped): (Vec<_>, Vec<_>) = decisions.into_iter()
+                .partition(|d| privacy_of(&d.provider_id) == Some(PrivacyLevel::Local));
+            record.dropped = dropped.into_iter().map(|d| d.provider_id).collect();
+            if kept.is_empty() {
+                let reason = format!("prompt contains {:?} and no local provider is available", record.kinds);
+                return Err(Box::new((reason, record)));
+            }
+            decisions = kept;
+        }
+        Some(PrivacyAction::Redact) => {
+            let mut redact = |text: &str, classification| match vault.as_deref_mut() {
+                Some(vault) => shield.tokenize(text, classification, vault),
+                None => shield.redact_classified(text, classification),
+            };
+            redacted_prompt = Some(redact(prompt, &classifications[0]));
+            let mut redacted = params.clone();
+            let mut rest = classifications[1..].iter();
+            visit_strings(&mut redacted, &mut |text| {
+                if let Some(classification) = rest.next().filter(|c| !c.findings.is_empty()) {
+                    *text = redact(text, classification);
+                }
+            });
+            redacted_params = Some(redacted);
+            for decision in decisions.iter_mut().filter(|d| privacy_of(&d.provider_id) == Some(PrivacyLevel::Cloud)) {
+                decision.shielded = true;
+                decision.explanation.push_str(&format!("; prompt redacted ({:?}), sent as Shielded", record.kinds));
+            }
+        }
+    }
+
+    record.provider_id = decisions.first().map(|d| d.provider_id.clone());
+    Ok(GuardedRoute { redacted_prompt, redacted_params, ..unchanged(decisions, record) })
+}
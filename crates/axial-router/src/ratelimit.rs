@@ -0,0 +1,198 @@
+use serde::{Serialize, Deserialize};
+use std::num::{NonZeroU32, NonZeroU64};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Matches the old fixed limit of 10 calls per second.
+pub const DEFAULT_REQUESTS_PER_MINUTE: u32 = 600;
+
+/// Per-provider quotas. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct RateLimits {
+    pub requests_per_minute: Option<u32>,
+    pub tokens_per_minute: Option<u64>,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self { requests_per_minute: Some(DEFAULT_REQUESTS_PER_MINUTE), tokens_per_minute: None }
+    }
+}
+
+impl std::str::FromStr for RateLimits {
+    type Err = anyhow::Error;
+
+    /// Parses `rpm=50,tpm=40000`; either part may be omitted, in which case it is unlimited.
+    /// Zero is rejected; omit the part for no limit.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut limits = RateLimits { requests_per_minute: None, tokens_per_minute: None };
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Expected key=value in rate limit '{}'", part))?;
+            let invalid = |e| anyhow::anyhow!("Invalid rate limit '{}': {}", part, e);
+            match key.trim() {
+                "rpm" => limits.requests_per_minute = Some(value.trim().parse::<NonZeroU32>().map_err(invalid)?.get()),
+                "tpm" => limits.tokens_per_minute = Some(value.trim().parse::<NonZeroU64>().map_err(invalid)?.get()),
+                other => return Err(anyhow::anyhow!("Unknown rate limit '{}' (expected rpm or tpm)", other)),
+            }
+        }
+        Ok(limits)
+    }
+}
+
+/// A bucket holding up to one minute's allowance, refilled continuously.
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    available: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn per_minute(amount: f64) -> Self {
+        Self { capacity: amount, available: amount, refill_per_sec: amount / 60.0, last_refill: Instant::now() }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Time until `amount` is available. A request larger than the whole bucket waits for a
+    /// full bucket rather than forever.
+    fn wait_for(&self, amount: f64) -> Duration {
+        let needed = amount.min(self.capacity) - self.available;
+        if needed <= 0.0 || self.refill_per_sec <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(needed / self.refill_per_sec)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitSnapshot {
+    pub limits: RateLimits,
+    pub requests_available: Option<u32>,
+    pub tokens_available: Option<u64>,
+}
+
+/// Request and token buckets for one provider.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limits: RateLimits,
+    buckets: Mutex<(Option<Bucket>, Option<Bucket>)>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(RateLimits::default())
+    }
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        let requests = limits.requests_per_minute.map(|n| Bucket::per_minute(n as f64));
+        let tokens = limits.tokens_per_minute.map(|n| Bucket::per_minute(n as f64));
+        Self { limits, buckets: Mutex::new((requests, tokens)) }
+    }
+
+    pub fn limits(&self) -> RateLimits {
+        self.limits
+    }
+
+    fn wait(buckets: &mut (Option<Bucket>, Option<Bucket>), tokens: u64) -> Duration {
+        let now = Instant::now();
+        let mut wait = Duration::ZERO;
+        if let Some(requests) = buckets.0.as_mut() {
+            requests.refill(now);
+            wait = wait.max(requests.wait_for(1.0));
+        }
+        if let Some(bucket) = buckets.1.as_mut() {
+            bucket.refill(now);
+            wait = wait.max(bucket.wait_for(tokens as f64));
+        }
+        wait
+    }
+
+    /// How long until a call of `tokens` would be admitted; zero if it would be now.
+    /// Consumes nothing.
+    pub fn peek(&self, tokens: u64) -> Duration {
+        Self::wait(&mut self.buckets.lock().unwrap(), tokens)
+    }
+
+    /// Takes one request and `tokens` from the buckets, or returns how long to wait.
+    pub fn try_acquire(&self, tokens: u64) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let wait = Self::wait(&mut buckets, tokens);
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+        if let Some(requests) = buckets.0.as_mut() {
+            requests.available -= 1.0;
+        }
+        if let Some(bucket) = buckets.1.as_mut() {
+            bucket.available -= (tokens as f64).min(bucket.capacity);
+        }
+        Ok(())
+    }
+
+    /// Gives back what `try_acquire` took for a call that was then never made.
+    pub fn release(&self, tokens: u64) {
+        let mut buckets = self.buckets.lock().unwrap();
+        if let Some(requests) = buckets.0.as_mut() {
+            requests.available = (requests.available + 1.0).min(requests.capacity);
+        }
+        if let Some(bucket) = buckets.1.as_mut() {
+            bucket.available = (bucket.available + (tokens as f64).min(bucket.capacity)).min(bucket.capacity);
+        }
+    }
+
+    /// Corrects the token bucket once the provider reports real usage. Overruns may leave the
+    /// bucket in debt, which delays the next call.
+    pub fn reconcile(&self, estimated: u64, actual: u64) {
+        let mut buckets = self.buckets.lock().unwrap();
+        if let Some(bucket) = buckets.1.as_mut() {
+            bucket.available = (bucket.available + estimated as f64 - actual as f64).min(bucket.capacity);
+        }
+    }
+
+    pub fn snapshot(&self) -> RateLimitSnapshot {
+        let mut buckets = self.buckets.lock().unwrap();
+        let now = Instant::now();
+        let available = |bucket: &mut Option<Bucket>| bucket.as_mut().map(|b| {
+            b.refill(now);
+            b.available.max(0.0).floor()
+        });
+        let requests = available(&mut buckets.0).map(|n| n as u32);
+        let tokens = available(&mut buckets.1).map(|n| n as u64);
+        RateLimitSnapshot { limits: self.limits, requests_available: requests, tokens_available: tokens }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peek_does_not_consume_and_acquire_reports_wait() {
+        let limiter = RateLimiter::new("rpm=2,tpm=1000".parse().unwrap());
+        for _ in 0..5 {
+            assert!(limiter.peek(100).is_zero());
+        }
+
+        limiter.try_acquire(600).unwrap();
+        let wait = limiter.try_acquire(600).unwrap_err();
+        // 200 tokens short at 1000/min is about 12s.
+        assert!(wait > Duration::from_secs(11) && wait <= Duration::from_secs(12));
+
+        limiter.reconcile(600, 100);
+        limiter.try_acquire(600).unwrap();
+        assert!(limiter.try_acquire(1).is_err(), "request bucket is empty");
+        assert!("rpm=ten".parse::<RateLimits>().is_err());
+        assert!("rpm=0".parse::<RateLimits>().is_err());
+        assert!("rpm=50,tpm=0".parse::<RateLimits>().is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::budget::{estimate_tokens, profile_constraint};
+use crate::strategy::HardConstraint;
+use axial_core::{Profile, TaskNode};
+use serde::{Serialize, Deserialize};
+
+/// Prompt size above which a node asks for the `long-context` capability.
+pub const LONG_CONTEXT_TOKENS: u64 = 32_000;
+
+/// What a task node needs from a provider, derived from its type, params and the active profile.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct NodeRequirements {
+    pub capabilities: Vec<String>,
+    /// Constraints the profile adds on top of the routing strategy.
+    pub constraints: Vec<HardConstraint>,
+}
+
+impl NodeRequirements {
+    /// Capabilities come from `task_type`, then from params:
+    /// - `tools` (non-empty) or `tool_use: true` adds `tool-use`
+    /// - `context_tokens`, or the estimated size of `context`/`instruction`, above
+    ///   `LONG_CONTEXT_TOKENS` adds `long-context`
+    /// - `requires` lists extra capabilities verbatim
+    ///
+    /// Profile constraints `route.require`, `route.local_only`, `route.max_latency_ms` and
+    /// `route.max_cost_per_1k` are applied last.
+    pub fn derive(node: &TaskNode, profile: Option<&Profile>) -> Self {
+        let mut reqs = Self::default();
+        for cap in Self::for_task_type(&node.task_type) {
+            reqs.require(cap);
+        }
+
+        let params = &node.params;
+        let has_tools = params.get("tools").and_then(|t| t.as_array()).is_some_and(|t| !t.is_empty());
+        if has_tools || params.get("tool_use").and_then(|v| v.as_bool()) == Some(true) {
+            reqs.require("tool-use");
+        }
+
+        let context_tokens = params.get("context_tokens").and_then(|v| v.as_u64()).unwrap_or_else(|| {
+            ["context", "instruction", "goal"].iter()
+                .filter_map(|k| params.get(*k).and_then(|v| v.as_str()))
+                .map(estimate_tokens)
+                .sum()
+        });
+        if context_tokens > LONG_CONTEXT_TOKENS {
+            reqs.require("long-context");
+        }
+
+        if let Some(extra) = params.get("requires").and_then(|v| v.as_array()) {
+            for cap in extra.iter().filter_map(|c| c.as_str()) {
+                reqs.require(cap);
+            }
+        }
+
+        if let Some(profile) = profile {
+            reqs.apply_profile(profile);
+        }
+        reqs
+    }
+
+    /// Base capabilities for the planner's task types. Unknown types fall back to plain generation.
+    pub fn for_task_type(task_type: &str) -> &'static [&'static str] {
+        match task_type {
+            "coding" => &["code-editing"],
+            "research" => &["text-generation", "complex-reasoning"],
+            "verification" => &["complex-reasoning", "code-editing"],
+            "nlp" => &["text-generation"],
+            _ => &["text-generation"],
+        }
+    }
+
+    fn require(&mut self, capability: &str) {
+        if !self.capabilities.iter().any(|c| c == capability) {
+            self.capabilities.push(capability.to_string());
+        }
+    }
+
+    fn apply_profile(&mut self, profile: &Profile) {
+        if let Some(extra) = profile_constraint(profile, "route.require").and_then(|v| v.as_array()) {
+            for cap in extra.iter().filter_map(|c| c.as_str()) {
+                self.require(cap);
+            }
+        }
+        if profile_constraint(profile, "route.local_only").and_then(|v| v.as_bool()) == Some(true) {
+            self.constraints.push(HardConstraint::LocalOnly);
+        }
+        if let Some(ms) = profile_constraint(profile, "route.max_latency_ms").and_then(|v| v.as_u64()) {
+            self.constraints.push(HardConstraint::MaxLatencyMs(ms.min(u32::MAX as u64) as u32));
+        }
+        if let Some(usd) = profile_constraint(profile, "route.max_cost_per_1k").and_then(|v| v.as_f64()) {
+            self.constraints.push(HardConstraint::MaxCostPer1k(usd));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axial_core::Constraint;
+    use serde_json::json;
+
+    #[test]
+    fn test_derive_from_task_type_params_and_profile() {
+        let node = TaskNode {
+            id: "edit".to_string(),
+            task_type: "coding".to_string(),
+            params: json!({ "tools": [{ "name": "apply_patch" }], "context_tokens": 120_000 }),
+            invariants: vec![],
+            approval_gate: None,
+        };
+        let profile = Profile {
+            name: "offline".to_string(),
+            constraints: vec![Constraint { key: "route.local_only".to_string(), value: json!(true) }],
+            preferred_tools: vec![],
+        };
+
+        let reqs = NodeRequirements::derive(&node, Some(&profile));
+        assert_eq!(reqs.capabilities, vec!["code-editing", "tool-use", "long-context"]);
+        assert_eq!(reqs.constraints, vec![HardConstraint::LocalOnly]);
+
+        let plain = TaskNode { task_type: "nlp".to_string(), params: json!({ "instruction": "hi" }), ..node };
+        assert_eq!(NodeRequirements::derive(&plain, None).capabilities, vec!["text-generation"]);
+    }
+}
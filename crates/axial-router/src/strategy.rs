@@ -0,0 +1,227 @@
+use crate::{PrivacyLevel, RouterError};
+use serde::{Serialize, Deserialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A dimension a strategy can optimise for.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    Privacy,
+    Latency,
+    Cost,
+    Quality,
+}
+
+impl Objective {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "privacy" => Some(Objective::Privacy),
+            "latency" | "performance" | "speed" => Some(Objective::Latency),
+            "cost" => Some(Objective::Cost),
+            "quality" => Some(Objective::Quality),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Objective::Privacy => "privacy",
+            Objective::Latency => "latency",
+            Objective::Cost => "cost",
+            Objective::Quality => "quality",
+        }
+    }
+}
+
+/// A requirement a provider must meet to be considered at all, regardless of score.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HardConstraint {
+    LocalOnly,
+    /// Local or shielded providers only.
+    NoCloud,
+    MaxLatencyMs(u32),
+    MaxCostPer1k(f64),
+}
+
+impl HardConstraint {
+    /// `latency_ms` is the learned latency and `cost_per_1k` the rate for the requested capabilities.
+    pub fn admits(&self, privacy: &PrivacyLevel, latency_ms: f64, cost_per_1k: f64) -> bool {
+        match self {
+            HardConstraint::LocalOnly => *privacy == PrivacyLevel::Local,
+            HardConstraint::NoCloud => *privacy != PrivacyLevel::Cloud,
+            HardConstraint::MaxLatencyMs(max) => latency_ms <= *max as f64,
+            HardConstraint::MaxCostPer1k(max) => cost_per_1k <= *max,
+        }
+    }
+}
+
+impl fmt::Display for HardConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HardConstraint::LocalOnly => write!(f, "local_only"),
+            HardConstraint::NoCloud => write!(f, "no_cloud"),
+            HardConstraint::MaxLatencyMs(ms) => write!(f, "max_latency={}ms", ms),
+            HardConstraint::MaxCostPer1k(usd) => write!(f, "max_cost={}", usd),
+        }
+    }
+}
+
+/// A weighted mix of objectives plus hard constraints.
+///
+/// Parsed from comma-separated terms: `privacy:0.6,cost:0.4,local_only,max_latency=500ms`.
+/// A bare objective has weight 1.0. The legacy names `privacy_first`, `performance` and
+/// `cost_efficient` are accepted as single-objective strategies.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Strategy {
+    pub weights: Vec<(Objective, f64)>,
+    #[serde(default)]
+    pub constraints: Vec<HardConstraint>,
+}
+
+impl Default for Strategy {
+    fn default() -> Self {
+        Self::performance()
+    }
+}
+
+impl Strategy {
+    pub fn single(objective: Objective) -> Self {
+        Self { weights: vec![(objective, 1.0)], constraints: Vec::new() }
+    }
+
+    pub fn privacy_first() -> Self {
+        Self::single(Objective::Privacy)
+    }
+
+    pub fn performance() -> Self {
+        Self::single(Objective::Latency)
+    }
+
+    pub fn cost_efficient() -> Self {
+        Self::single(Objective::Cost)
+    }
+
+    pub fn with_constraint(mut self, constraint: HardConstraint) -> Self {
+        self.constraints.push(constraint);
+        self
+    }
+
+    pub fn weight(&self, objective: Objective) -> f64 {
+        self.weights.iter().filter(|(o, _)| *o == objective).map(|(_, w)| w).sum()
+    }
+
+    pub fn admits(&self, privacy: &PrivacyLevel, latency_ms: f64, cost_per_1k: f64) -> bool {
+        self.constraints.iter().all(|c| c.admits(privacy, latency_ms, cost_per_1k))
+    }
+}
+
+fn invalid(msg: String) -> RouterError {
+    RouterError::InvalidStrategy(msg)
+}
+
+fn parse_latency_ms(value: &str) -> Option<u32> {
+    if let Some(ms) = value.strip_suffix("ms") {
+        ms.trim().parse().ok()
+    } else if let Some(secs) = value.strip_suffix('s') {
+        // `as` would quietly turn "-1s" into 0 and a huge value into u32::MAX.
+        let ms = secs.trim().parse::<f64>().ok()? * 1000.0;
+        (ms.is_finite() && (0.0..=u32::MAX as f64).contains(&ms)).then_some(ms as u32)
+    } else {
+        value.parse().ok()
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = RouterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut strategy = Strategy { weights: Vec::new(), constraints: Vec::new() };
+
+        for term in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            let term = term.to_ascii_lowercase().replace('-', "_");
+            match term.as_str() {
+                "privacy_first" => strategy.weights.push((Objective::Privacy, 1.0)),
+                "cost_efficient" => strategy.weights.push((Objective::Cost, 1.0)),
+                "local_only" | "local" => strategy.constraints.push(HardConstraint::LocalOnly),
+                "no_cloud" => strategy.constraints.push(HardConstraint::NoCloud),
+                _ => {
+                    if let Some((key, value)) = term.split_once('=') {
+                        let (key, value) = (key.trim(), value.trim());
+                        let constraint = match key {
+                            "max_latency" | "max_latency_ms" => parse_latency_ms(value)
+                                .map(HardConstraint::MaxLatencyMs)
+                                .ok_or_else(|| invalid(format!("Invalid latency '{}' in '{}'", value, term)))?,
+                            "max_cost" | "max_cost_per_1k" => value.trim_start_matches('$').parse::<f64>().ok()
+                                .filter(|v| v.is_finite() && *v >= 0.0)
+                                .map(HardConstraint::MaxCostPer1k)
+                                .ok_or_else(|| invalid(format!("Invalid cost '{}' in '{}'", value, term)))?,
+                            other => return Err(invalid(format!("Unknown constraint '{}'", other))),
+                        };
+                        strategy.constraints.push(constraint);
+                        continue;
+                    }
+
+                    let (name, weight) = match term.split_once(':') {
+                        Some((name, weight)) => {
+                            let weight: f64 = weight.trim().parse()
+                                .map_err(|_| invalid(format!("Invalid weight '{}' in '{}'", weight, term)))?;
+                            (name.trim(), weight)
+                        }
+                        None => (term.as_str(), 1.0),
+                    };
+                    let objective = Objective::parse(name).ok_or_else(|| invalid(format!(
+                        "Unknown strategy '{}' (expected privacy, latency, cost, quality or a constraint such as local_only, max_latency=500ms)",
+                        name
+                    )))?;
+                    if !weight.is_finite() || weight < 0.0 {
+                        return Err(invalid(format!("Weight for '{}' must be a non-negative number", name)));
+                    }
+                    strategy.weights.push((objective, weight));
+                }
+            }
+        }
+
+        if strategy.weights.is_empty() && strategy.constraints.is_empty() {
+            return Err(invalid(format!("Empty strategy '{}'", s)));
+        }
+        if strategy.weights.is_empty() {
+            // Constraints alone: rank the survivors by capability.
+            strategy.weights.push((Objective::Quality, 1.0));
+        }
+        Ok(strategy)
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms: Vec<String> = self.weights.iter()
+            .map(|(o, w)| format!("{}:{}", o.as_str(), w))
+            .chain(self.constraints.iter().map(|c| c.to_string()))
+            .collect();
+        write!(f, "{}", terms.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_weighted_strategy_with_constraints() {
+        let strategy: Strategy = "privacy:0.6, cost:0.4, local_only, max_latency=500ms".parse().unwrap();
+        assert_eq!(strategy.weight(Objective::Privacy), 0.6);
+        assert_eq!(strategy.weight(Objective::Cost), 0.4);
+        assert_eq!(strategy.constraints, vec![HardConstraint::LocalOnly, HardConstraint::MaxLatencyMs(500)]);
+        assert_eq!(strategy.to_string().parse::<Strategy>().unwrap(), strategy);
+
+        assert_eq!("privacy_first".parse::<Strategy>().unwrap(), Strategy::privacy_first());
+        assert!("fastest".parse::<Strategy>().is_err());
+        assert!("privacy:-1".parse::<Strategy>().is_err());
+        assert!("max_latency=soon".parse::<Strategy>().is_err());
+        assert!("max_latency=-1s".parse::<Strategy>().is_err());
+        assert!("max_latency=-5ms".parse::<Strategy>().is_err());
+        assert_eq!("max_latency=1.5s".parse::<Strategy>().unwrap().constraints, vec![HardConstraint::MaxLatencyMs(1500)]);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::classify::Sensitivity::{self, Pii, Proprietary, Secret};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// A named pattern in the built-in catalog. When `group` is set, only that capture group is
+/// reported, so context such as `AccountKey=` stays out of the span. `validate` runs on the
+/// reported text and drops matches that only look right, like card numbers failing Luhn.
+pub struct Detector {
+    pub id: &'static str,
+    pub kind: Sensitivity,
+    pub pattern: &'static str,
+    pub group: Option<usize>,
+    pub confidence: f32,
+    pub validate: Option<fn(&str) -> bool>,
+}
+
+const fn detector(id: &'static str, kind: Sensitivity, confidence: f32, pattern: &'static str) -> Detector {
+    Detector { id, kind, pattern, group: None, confidence, validate: None }
+}
+
+const fn captured(id: &'static str, kind: Sensitivity, confidence: f32, pattern: &'static str) -> Detector {
+    Detector { id, kind, pattern, group: Some(1), confidence, validate: None }
+}
+
+const fn validated(id: &'static str, kind: Sensitivity, confidence: f32, pattern: &'static str, validate: fn(&str) -> bool) -> Detector {
+    Detector { id, kind, pattern, group: None, confidence, validate: Some(validate) }
+}
+
+pub const DETECTORS: &[Detector] = &[
+    detector("aws_access_key", Secret, 0.95, r"\b(?:AKIA|ASIA|ABIA|ACCA)[0-9A-Z]{16}\b"),
+    captured("aws_secret_key", Secret, 0.9, r#"(?i)aws.{0,20}?(?:secret|private).{0,20}?["'\s:=]+([A-Za-z0-9/+=]{40})\b"#),
+    detector("gcp_api_key", Secret, 0.95, r"\bAIza[0-9A-Za-z_-]{35}\b"),
+    detector("gcp_service_account", Secret, 0.9, r#""type"\s*:\s*"service_account""#),
+    captured("azure_storage_key", Secret, 0.95, r"(?i)AccountKey=([A-Za-z0-9+/]{86}==)"),
+    detector("azure_client_secret", Secret, 0.85, r"\b[A-Za-z0-9_~.]{3}\dQ~[A-Za-z0-9_~.-]{31,34}"),
+    detector("github_token", Secret, 0.99, r"\b(?:gh[pousr]_[A-Za-z0-9]{36,255}|github_pat_[A-Za-z0-9_]{22,255})\b"),
+    detector("gitlab_token", Secret, 0.99, r"\bgl(?:pat|ptt|dt|rt|soat|cbt)-[A-Za-z0-9_-]{20,}"),
+    detector("anthropic_key", Secret, 0.99, r"\bsk-ant-(?:api|admin)\d{2}-[A-Za-z0-9_-]{32,}"),
+    detector("openai_key", Secret, 0.85, r"\bsk-(?:proj-|svcacct-|admin-)?[A-Za-z0-9_-]{20,}"),
+    detector("slack_token", Secret, 0.9, r"\bxox[abpors]-[A-Za-z0-9-]{10,}"),
+    detector("jwt", Secret, 0.9, r"\beyJ[A-Za-z0-9_-]{8,}\.eyJ[A-Za-z0-9_-]{8,}\.[A-Za-z0-9_-]{8,}"),
+    detector("private_key", Secret, 0.99, r"-----BEGIN (?:[A-Z0-9]+ )*PRIVATE KEY(?: BLOCK)?-----(?:(?s:.)*?-----END (?:[A-Z0-9]+ )*PRIVATE KEY(?: BLOCK)?-----)?"),
+    detector("assigned_secret", Secret, 0.6, r#"(?i)\b(?:api[_-]?key|secret|passw(?:or)?d|token)\b["']?\s*[:=]\s*["']?[^\s"']{8,}"#),
+    detector("email", Pii, 0.9, r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b"),
+    validated("us_ssn", Pii, 0.7, r"\b\d{3}-\d{2}-\d{4}\b", ssn_valid),
+    validated("credit_card", Pii, 0.9, r"\b(?:\d[ -]?){12,18}\d\b", luhn_valid),
+    validated("iban", Pii, 0.9, r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,3})?\b", iban_valid),
+    detector("phone", Pii, 0.6, r"\+\d{1,3}[ .-]?\(?\d{2,4}\)?[ .-]?\d{3,4}[ .-]?\d{3,4}\b|(?:\(\d{3}\) ?|\b\d{3}[.-])\d{3}[.-]\d{4}\b"),
+    validated("ipv4_address", Pii, 0.5, r"\b(?:\d{1,3}\.){3}\d{1,3}\b", ipv4_valid),
+    validated("ipv6_address", Pii, 0.5, r"(?i)\b(?:[0-9a-f]{1,4}:{1,2}){2,7}[0-9a-f]{1,4}\b", ipv6_valid),
+    detector("confidential_marker", Proprietary, 0.7, r"(?i)\b(?:confidential|proprietary|internal use only|do not distribute)\b"),
+    detector("copyright_notice", Proprietary, 0.8, r"(?i)copyright\s+(?:\(c\)|©)?\s*\d{4}.{0,80}all rights reserved"),
+];
+
+/// Looks up a catalog entry by id.
+pub fn find(id: &str) -> Option<&'static Detector> {
+    DETECTORS.iter().find(|d| d.id == id)
+}
+
+fn digits(s: &str) -> Vec<u32> {
+    s.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+/// Luhn checksum over the digits of `s`, ignoring separators. Card numbers are 13 to 19 digits.
+pub fn luhn_valid(s: &str) -> bool {
+    let digits = digits(s);
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits.iter().rev().enumerate()
+        .map(|(i, &d)| if i % 2 == 1 { let d = d * 2; if d > 9 { d - 9 } else { d } } else { d })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// ISO 13616 check: the rearranged IBAN read as a number must be 1 mod 97.
+pub fn iban_valid(s: &str) -> bool {
+    let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+    if !(15..=34).contains(&compact.len()) {
+        return false;
+    }
+    let (head, tail) = compact.split_at(4);
+    let mut remainder = 0u32;
+    for c in tail.chars().chain(head.chars()) {
+        let value = match c.to_digit(36) {
+            Some(v) => v,
+            None => return false,
+        };
+        remainder = if value >= 10 { (remainder * 100 + value) % 97 } else { (remainder * 10 + value) % 97 };
+    }
+    remainder == 1
+}
+
+/// Rejects numbers the SSA never issues: area 000, 666 or 9xx, group 00, serial 0000.
+fn ssn_valid(s: &str) -> bool {
+    let d = digits(s);
+    let area = d[0] * 100 + d[1] * 10 + d[2];
+    area != 0 && area != 666 && area < 900 && d[3..5] != [0, 0] && d[5..] != [0, 0, 0, 0]
+}
+
+fn ipv4_valid(s: &str) -> bool {
+    s.parse::<Ipv4Addr>().is_ok_and(|ip| !ip.is_unspecified())
+}
+
+/// Requires three or more non-empty groups so `a::b`-style paths in code don't count.
+fn ipv6_valid(s: &str) -> bool {
+    s.parse::<Ipv6Addr>().is_ok() && s.split(':').filter(|g| !g.is_empty()).count() >= 3
+}
+
+/// Shannon entropy of `s` in bits per character.
+pub fn shannon_entropy(s: &str) -> f64 {
+    let mut counts = std::collections::HashMap::new();
+    for c in s.chars() {
+        *counts.entry(c).or_insert(0usize) += 1;
+    }
+    let len = s.chars().count() as f64;
+    counts.values()
+        .map(|&n| {
+            let p = n as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Flags long random-looking strings that no catalog pattern knows, such as keys for
+/// in-house services. Hex strings have a lower ceiling (4 bits) than base64, so they get
+/// their own threshold.
+#[derive(Debug, Clone)]
+pub struct EntropyDetector {
+    pub min_len: usize,
+    pub base64_threshold: f64,
+    pub hex_threshold: f64,
+}
+
+pub const ENTROPY_DETECTOR_ID: &str = "high_entropy";
+
+impl Default for EntropyDetector {
+    fn default() -> Self {
+        Self { min_len: 20, base64_threshold: 4.3, hex_threshold: 3.0 }
+    }
+}
+
+impl EntropyDetector {
+    pub const PATTERN: &'static str = r"[A-Za-z0-9+/=_-]{20,}";
+
+    /// Confidence that `candidate` is a secret, or `None` if it doesn't look random enough.
+    /// Strings without both letters and digits are skipped; they are words and identifiers.
+    pub fn score(&self, candidate: &str) -> Option<f32> {
+        if candidate.len() < self.min_len
+            || !candidate.chars().any(|c| c.is_ascii_digit())
+            || !candidate.chars().any(|c| c.is_ascii_alphabetic())
+        {
+            return None;
+        }
+        let (threshold, ceiling) = if candidate.chars().all(|c| c.is_ascii_hexdigit()) {
+            (self.hex_threshold, 4.0)
+        } else {
+            (self.base64_threshold, 6.0)
+        };
+        let entropy = shannon_entropy(candidate);
+        if entropy < threshold {
+            return None;
+        }
+        Some((0.4 + 0.4 * ((entropy - threshold) / (ceiling - threshold)).min(1.0)) as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validators_and_entropy() {
+        assert!(luhn_valid("4111 1111 1111 1111"));
+        assert!(!luhn_valid("4111 1111 1111 1112"));
+        assert!(iban_valid("GB82 WEST 1234 5698 7654 32"));
+        assert!(!iban_valid("GB82 WEST 1234 5698 7654 33"));
+        assert!(!ssn_valid("000-12-3456"));
+        assert!(ipv6_valid("2001:db8::8a2e:370:7334"));
+        assert!(!ipv6_valid("ab::cd"));
+
+        let entropy = EntropyDetector::default();
+        assert!(entropy.score("q8Zr2LkW9vXe4TnB7yHc1MdF").is_some());
+        assert!(entropy.score("aaaaaaaaaaaaaaaaaaaa1111").is_none());
+        assert!(entropy.score("ThisIsAVeryLongIdentifierName").is_none());
+        assert!(DETECTORS.iter().all(|d| regex::Regex::new(d.pattern).is_ok()));
+    }
+}
@@ -0,0 +1,263 @@
+use crate::truth::{Severity, TruthEngine, TruthViolation};
+use anyhow::{Result, anyhow};
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Ledger `event` tag for a diff-scoped truth engine scan.
+pub const TRUTH_SCAN_EVENT: &str = "truth_scan";
+
+/// Files changed since a baseline, with the new-side line ranges each change touched.
+/// `None` means the whole file is new, as for untracked files.
+#[derive(Debug, Clone, Default)]
+pub struct ChangeSet {
+    files: BTreeMap<String, Option<Vec<(usize, usize)>>>,
+}
+
+impl ChangeSet {
+    /// Reads a zero-context unified diff (`git diff -U0`). Deleted files are left out.
+    pub fn parse(diff: &str) -> Self {
+        let mut files = BTreeMap::new();
+        let mut current: Option<String> = None;
+        for line in diff.lines() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                current = path.strip_prefix("b/").map(str::to_string);
+                if let Some(path) = &current {
+                    files.insert(path.clone(), Some(Vec::new()));
+                }
+            } else if let (Some(hunk), Some(path)) = (line.strip_prefix("@@ "), &current) {
+                // `@@ -a,b +c,d @@`: lines c..c+d on the new side; d defaults to 1.
+                let Some(added) = hunk.split(' ').find_map(|part| part.strip_prefix('+')) else { continue };
+                let mut parts = added.splitn(2, ',');
+                let start: usize = parts.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+                let count: usize = parts.next().and_then(|s| s.parse().ok()).unwrap_or(1);
+                if count > 0 {
+                    if let Some(Some(ranges)) = files.get_mut(path) {
+                        ranges.push((start, start + count - 1));
+                    }
+                }
+            }
+        }
+        Self { files }
+    }
+
+    pub fn add_whole_file(&mut self, path: &str) {
+        self.files.insert(path.to_string(), None);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn files(&self) -> Vec<String> {
+        self.files.keys().cloned().collect()
+    }
+
+    /// Whether a finding at `file:line` falls in the change. Line 0, a finding about the
+    /// file as a whole, counts if the file changed at all.
+    pub fn touches(&self, file: &str, line: usize) -> bool {
+        match self.files.get(file) {
+            Some(None) => true,
+            Some(Some(ranges)) => line == 0 || ranges.iter().any(|(start, end)| (*start..=*end).contains(&line)),
+            None => false,
+        }
+    }
+}
+
+/// One engine's findings split against the baseline.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiffReport {
+    pub engine: String,
+    pub base: String,
+    /// On lines the change touched and absent from the baseline.
+    pub new: Vec<TruthViolation>,
+    /// Present in the baseline too, or on lines the change didn't touch.
+    pub pre_existing: Vec<TruthViolation>,
+    /// In the baseline but gone now.
+    pub fixed: Vec<TruthViolation>,
+}
+
+impl DiffReport {
+    /// Splits `current` findings against `baseline` ones. Line numbers shift as code is
+    /// edited, so findings match on engine, rule, file and message, each baseline finding
+    /// matching at most one current finding.
+    pub fn classify(engine: &str, base: &str, current: Vec<TruthViolation>, baseline: Vec<TruthViolation>, changes: &ChangeSet) -> Self {
+        let key = |v: &TruthViolation| (v.engine.clone(), v.rule_id.clone(), v.file.clone(), v.message.clone());
+        let mut unmatched: Vec<Option<TruthViolation>> = baseline.into_iter().map(Some).collect();
+        let mut report = Self { engine: engine.to_string(), base: base.to_string(), ..Default::default() };
+        for violation in current {
+            let matched = unmatched.iter_mut().find(|b| b.as_ref().is_some_and(|b| key(b) == key(&violation)));
+            match matched {
+                Some(slot) => {
+                    slot.take();
+                    report.pre_existing.push(violation);
+                }
+                None if changes.touches(&violation.file, violation.line) => report.new.push(violation),
+                None => report.pre_existing.push(violation),
+            }
+        }
+        report.fixed = unmatched.into_iter().flatten().collect();
+        report
+    }
+
+    /// New findings at or above `min`; the ones that should halt a plan.
+    pub fn blocking(&self, min: Severity) -> Vec<&TruthViolation> {
+        self.new.iter().filter(|v| v.level() >= min).collect()
+    }
+
+    pub fn to_ledger_payload(&self, min: Severity) -> serde_json::Value {
+        serde_json::json!({
+            "event": TRUTH_SCAN_EVENT,
+            "engine": self.engine,
+            "base": self.base,
+            "new": self.new.len(),
+            "pre_existing": self.pre_existing.len(),
+            "fixed": self.fixed.len(),
+            "halt_severity": min,
+            "blocking": self.blocking(min),
+        })
+    }
+}
+
+/// Scans only what changed in a git checkout since `base`, comparing against the same files
+/// scanned at `base` in a temporary worktree.
+pub struct DiffScan {
+    root: PathBuf,
+    base: String,
+}
+
+fn git(root: &Path, args: &[&str]) -> Result<String> {
+    let out = Command::new("git").arg("-C").arg(root).args(args).output()
+        .map_err(|_| anyhow!("git not found in PATH"))?;
+    if !out.status.success() {
+        return Err(anyhow!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&out.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+impl DiffScan {
+    pub fn new(root: &Path, base: &str) -> Self {
+        Self { root: root.to_path_buf(), base: base.to_string() }
+    }
+
+    /// Pins the checkout's current commit as the baseline; call before an agent starts.
+    pub fn snapshot(root: &Path) -> Result<Self> {
+        let head = git(root, &["rev-parse", "HEAD"])?;
+        Ok(Self::new(root, head.trim()))
+    }
+
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// Committed, staged and unstaged changes since the baseline, plus untracked files.
+    pub fn changes(&self) -> Result<ChangeSet> {
+        let diff = git(&self.root, &[
+            "diff", "-U0", "--no-color", "--no-ext-diff", "--relative",
+            // `diff.noprefix` and `diff.mnemonicPrefix` would change the `b/` `parse` expects.
+            "--src-prefix=a/", "--dst-prefix=b/", &self.base,
+        ])?;
+        let mut changes = ChangeSet::parse(&diff);
+        for path in git(&self.root, &["ls-files", "--others", "--exclude-standard"])?.lines() {
+            changes.add_whole_file(path);
+        }
+        Ok(changes)
+    }
+
+    pub fn run(&self, engine: &dyn TruthEngine) -> Result<DiffReport> {
+        let changes = self.changes()?;
+        if changes.is_empty() {
+            return Ok(DiffReport { engine: engine.name().to_string(), base: self.base.clone(), ..Default::default() });
+        }
+        let files = changes.files();
+        let current = engine.run_files(&self.root, &files)?;
+        let baseline = self.scan_baseline(engine, &files)?;
+        Ok(DiffReport::classify(engine.name(), &self.base, current, baseline, &changes))
+    }
+
+    /// Runs `engine` over the files that already existed at the baseline.
+    fn scan_baseline(&self, engine: &dyn TruthEngine, files: &[String]) -> Result<Vec<TruthViolation>> {
+        let worktree = std::env::temp_dir().join(format!(
+            "axial-baseline-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let prefix = git(&self.root, &["rev-parse", "--show-prefix"])?;
+        git(&self.root, &["worktree", "add", "--detach", &worktree.to_string_lossy(), &self.base])?;
+        // `--relative` paths are relative to `root`, which may be below the repository top.
+        let root = worktree.join(prefix.trim());
+        let existing: Vec<String> = files.iter().filter(|f| root.join(f).exists()).cloned().collect();
+        let result = engine.run_files(&root, &existing);
+        let _ = git(&self.root, &["worktree", "remove", "--force", &worktree.to_string_lossy()]);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violation(rule: &str, file: &str, line: usize, severity: &str) -> TruthViolation {
+        TruthViolation {
+            engine: "semgrep".to_string(),
+            rule_id: rule.to_string(),
+            severity: severity.to_string(),
+            message: format!("{} finding", rule),
+            file: file.to_string(),
+            line,
+        }
+    }
+
+    #[test]
+    fn test_findings_split_into_new_pre_existing_and_fixed() {
+        let diff = "diff --git a/src/app.py b/src/app.py\n--- a/src/app.py\n+++ b/src/app.py\n\
+                    @@ -10,0 +11,3 @@ def handler():\n+a\n+b\n+c\n@@ -40 +43 @@\n-x\n+y\n\
+                    diff --git a/old.py b/old.py\n--- a/old.py\n+++ /dev/null\n@@ -1,2 +0,0 @@\n-p\n-q\n";
+        let mut changes = ChangeSet::parse(diff);
+        changes.add_whole_file("src/new.py");
+        assert_eq!(changes.files(), ["src/app.py", "src/new.py"]);
+        assert!(changes.touches("src/app.py", 13) && changes.touches("src/app.py", 43));
+        assert!(!changes.touches("src/app.py", 20) && !changes.touches("old.py", 1));
+
+        let current = vec![
+            violation("sql-injection", "src/app.py", 12, "ERROR"),
+            violation("weak-hash", "src/app.py", 25, "ERROR"),
+            violation("debug-enabled", "src/app.py", 20, "WARNING"),
+            violation("open-redirect", "src/new.py", 3, "WARNING"),
+        ];
+        // The weak hash moved down by three lines; it is the same finding.
+        let baseline = vec![violation("weak-hash", "src/app.py", 22, "ERROR"), violation("eval", "src/app.py", 5, "ERROR")];
+        let report = DiffReport::classify("semgrep", "abc123", current, baseline, &changes);
+
+        let rules = |vs: &[TruthViolation]| vs.iter().map(|v| v.rule_id.clone()).collect::<Vec<_>>();
+        assert_eq!(rules(&report.new), ["sql-injection", "open-redirect"]);
+        assert_eq!(rules(&report.pre_existing), ["weak-hash", "debug-enabled"]);
+        assert_eq!(rules(&report.fixed), ["eval"]);
+        assert_eq!(report.blocking(Severity::High).len(), 1);
+        assert_eq!(report.blocking(Severity::Medium).len(), 2);
+    }
+
+    #[test]
+    fn test_changes_ignore_the_users_diff_prefix_settings() {
+        let root = std::env::temp_dir().join(format!("axial-diffscan-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("app.py"), "a\n").unwrap();
+        for args in [
+            &["init", "-q"][..],
+            &["config", "diff.noprefix", "true"],
+            &["add", "."],
+            &["-c", "user.name=axial", "-c", "user.email=axial@example.com", "commit", "-qm", "base"],
+        ] {
+            git(&root, args).unwrap();
+        }
+        let scan = DiffScan::snapshot(&root).unwrap();
+        std::fs::write(root.join("app.py"), "a\nb\n").unwrap();
+
+        let changes = scan.changes().unwrap();
+        assert_eq!(changes.files(), ["app.py"]);
+        assert!(changes.touches("app.py", 2));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,277 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+/// Ledger `event` tag for engaging and re-arming the kill switch.
+pub const KILL_SWITCH_EVENT: &str = "shield_kill_switch";
+
+pub const KILL_SWITCH_FILE: &str = "kill-switch.json";
+
+/// What an engaged kill switch stops: everything, calls to one provider, or one run.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "scope", content = "id", rename_all = "snake_case")]
+pub enum KillScope {
+    Global,
+    Provider(String),
+    Run(String),
+}
+
+impl KillScope {
+    /// Global unless a provider or run is named; naming both is an error.
+    pub fn from_parts(provider: Option<String>, run: Option<String>) -> Result<Self> {
+        match (provider, run) {
+            (Some(_), Some(_)) => Err(anyhow!("A kill switch scopes to a provider or a run, not both")),
+            (Some(provider), None) => Ok(Self::Provider(provider)),
+            (None, Some(run)) => Ok(Self::Run(run)),
+            (None, None) => Ok(Self::Global),
+        }
+    }
+}
+
+impl std::fmt::Display for KillScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Global => write!(f, "global"),
+            Self::Provider(id) => write!(f, "provider {}", id),
+            Self::Run(id) => write!(f, "run {}", id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KillEntry {
+    #[serde(flatten)]
+    pub scope: KillScope,
+    pub reason: Option<String>,
+    /// Where it was engaged from: `cli`, `daemon`, `ui`.
+    pub source: String,
+    pub engaged_at: DateTime<Utc>,
+}
+
+impl KillEntry {
+    /// Whether this entry stops a call to `provider` within `run`.
+    pub fn applies_to(&self, provider: Option<&str>, run: Option<&str>) -> bool {
+        match &self.scope {
+            KillScope::Global => true,
+            KillScope::Provider(id) => provider == Some(id.as_str()),
+            KillScope::Run(id) => run == Some(id.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KillAction {
+    Engaged,
+    Rearmed,
+}
+
+/// An engage or re-arm, for the caller to write to the ledger.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KillRecord {
+    pub action: KillAction,
+    #[serde(flatten)]
+    pub scope: KillScope,
+    pub reason: Option<String>,
+    pub source: String,
+    pub at: DateTime<Utc>,
+}
+
+impl KillRecord {
+    pub fn to_ledger_payload(&self) -> serde_json::Value {
+        let mut payload = serde_json::to_value(self).unwrap_or_else(|_| serde_json::json!({}));
+        payload["event"] = serde_json::json!(KILL_SWITCH_EVENT);
+        payload
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct KillFile {
+    engaged: Vec<KillEntry>,
+}
+
+struct KillState {
+    engaged: Vec<KillEntry>,
+    /// Modification time and size of the file when last read, to notice other writers.
+    modified: Option<(SystemTime, u64)>,
+}
+
+/// Engaged kill switches, persisted to a file so they survive restarts and are shared by
+/// every process (CLI, daemon, proxy, UI) using the same file. An engaged scope stays
+/// engaged until explicitly re-armed.
+pub struct KillSwitch {
+    path: Option<PathBuf>,
+    state: RwLock<KillState>,
+}
+
+impl KillSwitch {
+    pub fn open(path: &Path) -> Result<Self> {
+        let switch = Self { path: Some(path.to_path_buf()), state: RwLock::new(KillState { engaged: Vec::new(), modified: None }) };
+        switch.reload()?;
+        Ok(switch)
+    }
+
+    /// A kill switch that lives only as long as this process.
+    pub fn in_memory() -> Self {
+        Self { path: None, state: RwLock::new(KillState { engaged: Vec::new(), modified: None }) }
+    }
+
+    fn modified(path: &Path) -> Option<(SystemTime, u64)> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+
+    fn reload(&self) -> Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let modified = Self::modified(path);
+        let engaged = match modified {
+            Some(_) => {
+                let content = std::fs::read_to_string(path)?;
+                serde_json::from_str::<KillFile>(&content)
+                    .map_err(|e| anyhow!("Invalid kill switch file {:?}: {}", path, e))?
+                    .engaged
+            }
+            None => Vec::new(),
+        };
+        *self.state.write().unwrap() = KillState { engaged, modified };
+        Ok(())
+    }
+
+    /// Picks up changes other processes made to the file. Returns whether anything changed.
+    /// A file that can't be read keeps the last known state rather than disarming.
+    pub fn refresh(&self) -> bool {
+        let Some(path) = &self.path else { return false };
+        if Self::modified(path) == self.state.read().unwrap().modified {
+            return false;
+        }
+        self.reload().is_ok()
+    }
+
+    /// Runs `change` holding an exclusive lock on a sibling `.lock` file, with the state
+    /// reread from disk, so a process engaging or re-arming at the same moment as another
+    /// can't save over its change.
+    fn locked<T>(&self, change: impl FnOnce() -> Result<T>) -> Result<T> {
+        let Some(path) = &self.path else { return change() };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::OpenOptions::new().create(true).truncate(false).write(true).open(path.with_extension("lock"))?;
+        let mut lock = fd_lock::RwLock::new(file);
+        let _guard = lock.write()?;
+        // Like `refresh`, an unreadable file keeps the last known state rather than disarming.
+        let _ = self.reload();
+        change()
+    }
+
+    fn save(&self, engaged: &[KillEntry]) -> Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(&KillFile { engaged: engaged.to_vec() })?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Engages `scope`. Engaging an already engaged scope keeps the original entry.
+    pub fn engage(&self, scope: KillScope, reason: Option<String>, source: &str) -> Result<KillRecord> {
+        self.locked(|| {
+            let at = Utc::now();
+            let mut state = self.state.write().unwrap();
+            if !state.engaged.iter().any(|e| e.scope == scope) {
+                let mut engaged = state.engaged.clone();
+                engaged.push(KillEntry { scope: scope.clone(), reason: reason.clone(), source: source.to_string(), engaged_at: at });
+                self.save(&engaged)?;
+                state.engaged = engaged;
+                state.modified = self.path.as_deref().and_then(Self::modified);
+            }
+            Ok(KillRecord { action: KillAction::Engaged, scope, reason, source: source.to_string(), at })
+        })
+    }
+
+    /// Re-arms `scope`. Only that exact scope is lifted; re-arming a run leaves a global
+    /// kill in place.
+    pub fn rearm(&self, scope: &KillScope, reason: Option<String>, source: &str) -> Result<KillRecord> {
+        self.locked(|| {
+            let mut state = self.state.write().unwrap();
+            if !state.engaged.iter().any(|e| &e.scope == scope) {
+                return Err(anyhow!("Kill switch is not engaged for {}", scope));
+            }
+            let engaged: Vec<KillEntry> = state.engaged.iter().filter(|e| &e.scope != scope).cloned().collect();
+            self.save(&engaged)?;
+            state.engaged = engaged;
+            state.modified = self.path.as_deref().and_then(Self::modified);
+            Ok(KillRecord { action: KillAction::Rearmed, scope: scope.clone(), reason, source: source.to_string(), at: Utc::now() })
+        })
+    }
+
+    pub fn engaged(&self) -> Vec<KillEntry> {
+        self.refresh();
+        self.state.read().unwrap().engaged.clone()
+    }
+
+    /// The entry that stops a call to `provider` within `run`, if any.
+    pub fn check(&self, provider: Option<&str>, run: Option<&str>) -> Option<KillEntry> {
+        self.refresh();
+        self.state.read().unwrap().engaged.iter().find(|e| e.applies_to(provider, run)).cloned()
+    }
+
+    pub fn is_global(&self) -> bool {
+        self.check(None, None).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_persist_across_instances_until_rearmed() {
+        let path = std::env::temp_dir().join(format!("axial-kill-test-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let switch = KillSwitch::open(&path).unwrap();
+        switch.engage(KillScope::Provider("openai".to_string()), Some("leak".to_string()), "cli").unwrap();
+        switch.engage(KillScope::Run("run-1".to_string()), None, "daemon").unwrap();
+
+        let other = KillSwitch::open(&path).unwrap();
+        assert!(other.check(Some("openai"), None).is_some());
+        assert!(other.check(Some("ollama"), Some("run-1")).is_some());
+        assert!(other.check(Some("ollama"), Some("run-2")).is_none());
+        assert!(!other.is_global());
+
+        let record = other.rearm(&KillScope::Run("run-1".to_string()), None, "cli").unwrap();
+        assert_eq!(record.to_ledger_payload()["action"], "rearmed");
+        assert!(other.rearm(&KillScope::Global, None, "cli").is_err());
+
+        // The first instance sees the other process's change on its next check.
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        switch.engage(KillScope::Global, None, "ui").unwrap();
+        assert!(switch.check(Some("ollama"), Some("run-1")).is_some_and(|e| e.scope == KillScope::Global));
+        assert_eq!(KillSwitch::open(&path).unwrap().engaged().len(), 2);
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("lock"));
+    }
+
+    #[test]
+    fn test_concurrent_writers_keep_each_others_scopes() {
+        let path = std::env::temp_dir().join(format!("axial-kill-race-test-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let writers: Vec<_> = (0..8).map(|i| {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                // One instance per writer, as separate processes would have.
+                let switch = KillSwitch::open(&path).unwrap();
+                for j in 0..5 {
+                    switch.engage(KillScope::Run(format!("run-{}-{}", i, j)), None, "cli").unwrap();
+                }
+            })
+        }).collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_eq!(KillSwitch::open(&path).unwrap().engaged().len(), 40);
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("lock"));
+    }
+}
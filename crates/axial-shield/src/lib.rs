@@ -1,145 +1,344 @@
-pub mod proxy;
-pub mod truth;
-
-pub use proxy::ShieldProxy;
-pub use truth::TruthEngine;
-use serde::{Serialize, Deserialize};
-use anyhow::{Result, anyhow};
-use regex::Regex;
-use std::collections::HashSet;
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct ShieldConfig {
-    pub allowed_domains: HashSet<String>,
-    pub pii_patterns: Vec<String>,
-    pub redacted_placeholder: String,
-}
-
-pub struct Shield {
-    config: ShieldConfig,
-    patterns: Vec<Regex>,
-    kill_switch: std::sync::atomic::AtomicBool,
-}
-
-impl Shield {
-    pub fn new(config: ShieldConfig) -> Result<Self> {
-        let patterns = config.pii_patterns.iter()
-            .map(|p| Regex::new(p))
-            .collect::<Result<Vec<_>, _>>()?;
-        
-        Ok(Self { 
-            config, 
-            patterns,
-            kill_switch: std::sync::atomic::AtomicBool::new(false),
-        })
-    }
-
-    pub fn redact(&self, input: &str) -> String {
-        if self.kill_switch.load(std::sync::atomic::Ordering::Relaxed) {
-            return "[SHIELD KILL SWITCH ACTIVE]".to_string();
-        }
-
-        let mut output = input.to_string();
-        for pattern in &self.patterns {
-            output = pattern.replace_all(&output, &self.config.redacted_placeholder).to_string();
-        }
-        
-        // v1-max: Local Model Pass Hook
-        // output = self.local_model_redact(&output);
-        
-        output
-    }
-
-    pub fn trigger_kill_switch(&self) {
-        self.kill_switch.store(true, std::sync::atomic::Ordering::Relaxed);
-    }
-
-    pub fn validate_request(&self, domain: &str) -> Result<()> {
-        if self.kill_switch.load(std::sync::atomic::Ordering::Relaxed) {
-            return Err(anyhow!("Shield Kill Switch Active: Request Blocked"));
-        }
-
-        if self.config.allowed_domains.contains(domain) {
-            Ok(())
-        } else {
-            Err(anyhow!("Security Violation: Domain {} is not in the allowlist", domain))
-        }
-    }
-
-    pub fn validate_file_export(&self, path: &std::path::Path) -> Result<()> {
-        // v1-max: Ensure file export is within allowed workspace boundaries
-        let workspace_root = std::env::current_dir()?;
-        let canonical_path = if path.exists() {
-            path.canonicalize()?
-        } else {
-            path.to_path_buf()
-        };
-
-        if canonical_path.starts_with(&workspace_root) {
-            Ok(())
-        } else {
-            Err(anyhow!("Security Violation: File export to {:?} is outside allowed boundaries", path))
-        }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::collections::HashSet;
-
-    fn mock_config() -> ShieldConfig {
-        let mut allowed_domains = HashSet::new();
-        allowed_domains.insert("localhost".to_string());
-        allowed_domains.insert("api.openai.com".to_string());
-
-        ShieldConfig {
-            allowed_domains,
-            pii_patterns: vec![
-                r"\b\d{4}-\d{4}-\d{4}-\d{4}\b".to_string(), // Fake CC
-                r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Z|a-z]{2,}\b".to_string(), // Email
-            ],
-            redacted_placeholder: "[REDACTED]".to_string(),
-        }
-    }
-
-    #[test]
-    fn test_shield_redact() {
-        let shield = Shield::new(mock_config()).unwrap();
-        let input = "Contact me at test@example.com or use card 1234-5678-1234-5678";
-        let output = shield.redact(input);
-        assert!(!output.contains("test@example.com"));
-        assert!(!output.contains("1234-5678-1234-5678"));
-        assert!(output.contains("[REDACTED]"));
-    }
-
-    #[test]
-    fn test_shield_domain_validation() {
-        let shield = Shield::new(mock_config()).unwrap();
-        assert!(shield.validate_request("localhost").is_ok());
-        assert!(shield.validate_request("malicious.com").is_err());
-    }
-
-    #[test]
-    fn test_shield_kill_switch() {
-        let shield = Shield::new(mock_config()).unwrap();
-        shield.trigger_kill_switch();
-        assert!(shield.validate_request("localhost").is_err());
-        assert_eq!(shield.redact("some text"), "[SHIELD KILL SWITCH ACTIVE]");
-    }
-}
-
-pub struct ShieldInterceptor {
-    shield: std::sync::Arc<Shield>,
-}
-
-impl ShieldInterceptor {
-    pub fn new(shield: std::sync::Arc<Shield>) -> Self {
-        Self { shield }
-    }
-
-    pub async fn intercept_and_scrub(&self, content: &str, target_domain: &str) -> Result<String> {
-        self.shield.validate_request(target_domain)?;
-        Ok(self.shield.redact(content))
-    }
-}
+pub mod classify;
+pub mod detectors;
+pub mod diffscan;
+pub mod killswitch;
+pub mod mitm;
+pub mod policy;
+pub mod proxy;
+pub mod sarif;
+pub mod scanner;
+pub mod stream;
+pub mod truth;
+pub mod vault;
+pub mod workspace;
+
+pub use proxy::{ProxyAudit, ShieldProxy};
+pub use mitm::CertificateAuthority;
+pub use truth::{Severity, TruthEngine, TruthViolation};
+pub use classify::{Classification, Classifier, Finding, Sensitivity};
+pub use detectors::{Detector, EntropyDetector, DETECTORS};
+pub use diffscan::{ChangeSet, DiffReport, DiffScan};
+pub use killswitch::{KillEntry, KillRecord, KillScope, KillSwitch};
+pub use policy::{DomainRule, RequestTarget};
+pub use scanner::SecretScanner;
+pub use stream::StreamRedactor;
+pub use vault::{StreamRestorer, Vault, VaultKey};
+pub use workspace::WorkspaceGuard;
+use serde::{Serialize, Deserialize};
+use anyhow::{Result, anyhow};
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+fn default_placeholder() -> String {
+    "[REDACTED]".to_string()
+}
+
+/// Shield policy, as stored in a policy file. `allowed_domains` are exact hosts kept for
+/// older configs; `allow` and `deny` take wildcard, port and method rules.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShieldConfig {
+    #[serde(default)]
+    pub allowed_domains: HashSet<String>,
+    #[serde(default)]
+    pub allow: Vec<DomainRule>,
+    /// Checked first; a match blocks the request even if an allow rule matches too.
+    #[serde(default)]
+    pub deny: Vec<DomainRule>,
+    #[serde(default)]
+    pub pii_patterns: Vec<String>,
+    /// Patterns whose matches become `<<LABEL_n>>` tokens named after their key.
+    #[serde(default)]
+    pub labeled_patterns: std::collections::BTreeMap<String, String>,
+    #[serde(default = "default_placeholder")]
+    pub redacted_placeholder: String,
+    /// New truth engine findings at or above this severity halt a plan.
+    #[serde(default)]
+    pub halt_severity: Severity,
+}
+
+impl Default for ShieldConfig {
+    /// HTTPS to the OpenAI and Anthropic APIs only, redacting SSNs and OpenAI keys.
+    fn default() -> Self {
+        let https = |host: &str| DomainRule { host: host.to_string(), ports: vec![443], methods: Vec::new() };
+        Self {
+            allowed_domains: HashSet::new(),
+            allow: vec![https("api.openai.com"), https("api.anthropic.com")],
+            deny: Vec::new(),
+            pii_patterns: vec![r"\d{3}-\d{2}-\d{4}".to_string(), r"sk-[a-zA-Z0-9]{32,}".to_string()],
+            labeled_patterns: Default::default(),
+            redacted_placeholder: default_placeholder(),
+            halt_severity: Severity::default(),
+        }
+    }
+}
+
+impl ShieldConfig {
+    /// Reads a JSON policy file.
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read shield policy {:?}: {}", path, e))?;
+        serde_json::from_str(&content).map_err(|e| anyhow!("Invalid shield policy {:?}: {}", path, e))
+    }
+
+    /// `allow` plus the legacy exact-match domains.
+    fn allow_rules(&self) -> Vec<DomainRule> {
+        let mut rules = self.allow.clone();
+        rules.extend(self.allowed_domains.iter().map(|d| DomainRule::host(d)));
+        rules
+    }
+}
+
+/// The active policy with its patterns compiled; swapped whole on reload.
+struct ActivePolicy {
+    config: ShieldConfig,
+    allow: Vec<DomainRule>,
+    patterns: Vec<Regex>,
+    labeled: Vec<(String, Regex)>,
+}
+
+impl ActivePolicy {
+    fn compile(config: ShieldConfig) -> Result<Self> {
+        let patterns = config.pii_patterns.iter()
+            .map(|p| Regex::new(p))
+            .collect::<Result<Vec<_>, _>>()?;
+        let labeled = config.labeled_patterns.iter()
+            .map(|(label, p)| Regex::new(p).map(|r| (label.clone(), r)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { allow: config.allow_rules(), config, patterns, labeled })
+    }
+}
+
+pub struct Shield {
+    policy: RwLock<ActivePolicy>,
+    classifier: Classifier,
+    kill_switch: Arc<KillSwitch>,
+}
+
+impl Shield {
+    /// A shield with a kill switch private to this process.
+    pub fn new(config: ShieldConfig) -> Result<Self> {
+        Self::with_kill_switch(config, Arc::new(KillSwitch::in_memory()))
+    }
+
+    /// A shield that honors `kill_switch`, usually file-backed and shared with other shields
+    /// and processes.
+    pub fn with_kill_switch(config: ShieldConfig, kill_switch: Arc<KillSwitch>) -> Result<Self> {
+        Ok(Self { 
+            policy: RwLock::new(ActivePolicy::compile(config)?),
+            classifier: Classifier::default(),
+            kill_switch,
+        })
+    }
+
+    /// Replaces the policy in place, so holders of this shield (the proxy, the daemon) pick
+    /// it up without restarting. An invalid policy leaves the current one active.
+    pub fn reload(&self, config: ShieldConfig) -> Result<()> {
+        let compiled = ActivePolicy::compile(config)?;
+        *self.policy.write().unwrap() = compiled;
+        Ok(())
+    }
+
+    pub fn config(&self) -> ShieldConfig {
+        self.policy.read().unwrap().config.clone()
+    }
+
+    pub fn redact(&self, input: &str) -> String {
+        self.redact_with_count(input).0
+    }
+
+    /// Like `redact`, also returning how many matches were replaced.
+    pub fn redact_with_count(&self, input: &str) -> (String, usize) {
+        if self.kill_switch_active() {
+            return ("[SHIELD KILL SWITCH ACTIVE]".to_string(), 0);
+        }
+
+        let policy = self.policy.read().unwrap();
+        let mut output = input.to_string();
+        let mut count = 0;
+        for pattern in &policy.patterns {
+            count += pattern.find_iter(&output).count();
+            output = pattern.replace_all(&output, &policy.config.redacted_placeholder).to_string();
+        }
+        
+        // v1-max: Local Model Pass Hook
+        // output = self.local_model_redact(&output);
+        
+        (output, count)
+    }
+
+    /// A chunked counterpart to `redact`, using the configured patterns.
+    pub fn stream_redactor(&self) -> Result<StreamRedactor> {
+        self.stream_redactor_with_catalog(None)
+    }
+
+    /// The configured patterns plus catalog detectors at or above `min_confidence`, for
+    /// output that may carry credentials, like build logs and tool stdout.
+    pub fn stream_redactor_with_catalog(&self, min_confidence: Option<f32>) -> Result<StreamRedactor> {
+        let policy = self.policy.read().unwrap();
+        let patterns: Vec<&str> = policy.patterns.iter().chain(policy.labeled.iter().map(|(_, p)| p))
+            .map(|p| p.as_str())
+            .collect();
+        let detectors: Vec<&Detector> = match min_confidence {
+            Some(min) => DETECTORS.iter().filter(|d| d.confidence >= min).collect(),
+            None => Vec::new(),
+        };
+        StreamRedactor::with_detectors(&patterns, &detectors, &policy.config.redacted_placeholder)
+    }
+
+    /// Runs the detector catalog, counting matches of the configured PII patterns as PII too.
+    pub fn classify(&self, input: &str) -> Classification {
+        let mut classification = self.classifier.classify(input);
+        let policy = self.policy.read().unwrap();
+        let configured = policy.patterns.iter().map(|p| ("configured_pattern", p))
+            .chain(policy.labeled.iter().map(|(label, p)| (label.as_str(), p)));
+        for (label, pattern) in configured {
+            for m in pattern.find_iter(input) {
+                classification.findings.push(Finding {
+                    kind: Sensitivity::Pii,
+                    detector: label.to_string(),
+                    start: m.start(),
+                    end: m.end(),
+                    confidence: 1.0,
+                });
+            }
+        }
+        classification.findings.sort_by_key(|f| (f.start, f.end));
+        classification
+    }
+
+    /// Replaces each finding with a reversible token from `vault`, e.g. `<<EMAIL_1>>`.
+    pub fn tokenize(&self, input: &str, classification: &Classification, vault: &mut Vault) -> String {
+        if self.kill_switch_active() {
+            return "[SHIELD KILL SWITCH ACTIVE]".to_string();
+        }
+        classification.replace_with(input, |finding, text| vault.token_for(&vault::token_label(&finding.detector), text))
+    }
+
+    /// Redacts the classifier's findings and then the configured patterns.
+    pub fn redact_classified(&self, input: &str, classification: &Classification) -> String {
+        let placeholder = self.policy.read().unwrap().config.redacted_placeholder.clone();
+        self.redact(&classification.redact(input, &placeholder))
+    }
+
+    pub fn kill_switch(&self) -> &Arc<KillSwitch> {
+        &self.kill_switch
+    }
+
+    /// Whether the global kill switch is engaged. Provider and run scopes are checked by
+    /// whoever makes those calls, through `kill_switch().check`.
+    pub fn kill_switch_active(&self) -> bool {
+        self.kill_switch.is_global()
+    }
+
+    pub fn validate_request(&self, domain: &str) -> Result<()> {
+        self.check_request(&RequestTarget::host(domain))
+    }
+
+    /// Checks a request against the deny and allow rules, including port and method where known.
+    pub fn check_request(&self, target: &RequestTarget) -> Result<()> {
+        if self.kill_switch_active() {
+            return Err(anyhow!("Shield Kill Switch Active: Request Blocked"));
+        }
+
+        let policy = self.policy.read().unwrap();
+        policy::evaluate(&policy.allow, &policy.config.deny, target)
+            .map_err(|reason| anyhow!("Security Violation: {}", reason))
+    }
+
+    /// Checks a CONNECT tunnel whose requests won't be visible, with `policy::evaluate_tunnel`.
+    pub fn check_tunnel(&self, host: &str, port: u16) -> Result<()> {
+        if self.kill_switch_active() {
+            return Err(anyhow!("Shield Kill Switch Active: Request Blocked"));
+        }
+
+        let policy = self.policy.read().unwrap();
+        policy::evaluate_tunnel(&policy.allow, &policy.config.deny, host, port)
+            .map_err(|reason| anyhow!("Security Violation: {}", reason))
+    }
+
+    /// Checks an export against a `WorkspaceGuard` rooted at the current directory.
+    pub fn validate_file_export(&self, path: &std::path::Path) -> Result<()> {
+        WorkspaceGuard::for_workspace(&std::env::current_dir()?)?
+            .check_write(path)
+            .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn mock_config() -> ShieldConfig {
+        let mut allowed_domains = HashSet::new();
+        allowed_domains.insert("localhost".to_string());
+        allowed_domains.insert("api.openai.com".to_string());
+
+        ShieldConfig {
+            allowed_domains,
+            allow: Vec::new(),
+            deny: Vec::new(),
+            pii_patterns: vec![
+                r"\b\d{4}-\d{4}-\d{4}-\d{4}\b".to_string(), // Fake CC
+                r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Z|a-z]{2,}\b".to_string(), // Email
+            ],
+            labeled_patterns: Default::default(),
+            redacted_placeholder: "[REDACTED]".to_string(),
+            halt_severity: Severity::default(),
+        }
+    }
+
+    #[test]
+    fn test_shield_redact() {
+        let shield = Shield::new(mock_config()).unwrap();
+        let input = "Contact me at test@example.com or use card 1234-5678-1234-5678";
+        let output = shield.redact(input);
+        assert!(!output.contains("test@example.com"));
+        assert!(!output.contains("1234-5678-1234-5678"));
+        assert!(output.contains("[REDACTED]"));
+    }
+
+    #[test]
+    fn test_shield_domain_validation() {
+        let shield = Shield::new(mock_config()).unwrap();
+        assert!(shield.validate_request("localhost").is_ok());
+        assert!(shield.validate_request("malicious.com").is_err());
+    }
+
+    #[test]
+    fn test_shield_kill_switch() {
+        let shield = Shield::new(mock_config()).unwrap();
+        shield.kill_switch().engage(KillScope::Global, None, "test").unwrap();
+        assert!(shield.validate_request("localhost").is_err());
+        assert_eq!(shield.redact("some text"), "[SHIELD KILL SWITCH ACTIVE]");
+    }
+
+    #[test]
+    fn test_shield_tokenize_round_trips_through_vault() {
+        let mut config = mock_config();
+        config.labeled_patterns.insert("EMPLOYEE_ID".to_string(), r"\bEMP-\d{5}\b".to_string());
+        let shield = Shield::new(config).unwrap();
+        let mut vault = Vault::in_memory();
+
+        let input = "EMP-12345 emailed test@example.com, then test@example.com again";
+        let tokenized = shield.tokenize(input, &shield.classify(input), &mut vault);
+        assert_eq!(tokenized, "<<EMPLOYEE_ID_1>> emailed <<EMAIL_1>>, then <<EMAIL_1>> again");
+        assert_eq!(vault.restore(&tokenized), input);
+    }
+}
+
+pub struct ShieldInterceptor {
+    shield: std::sync::Arc<Shield>,
+}
+
+impl ShieldInterceptor {
+    pub fn new(shield: std::sync::Arc<Shield>) -> Self {
+        Self { shield }
+    }
+
+    pub async fn intercept_and_scrub(&self, content: &str, target_domain: &str) -> Result<String> {
+        self.shield.validate_request(target_domain)?;
+        Ok(self.shield.redact(content))
+    }
+}
@@ -0,0 +1,152 @@
+use anyhow::{Result, anyhow};
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DistinguishedName, DnType,
+    ExtendedKeyUsagePurpose, IsCa, KeyPair, KeyUsagePurpose, SanType,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio_rustls::rustls;
+
+pub const CA_CERT_FILE: &str = "shield-ca.pem";
+pub const CA_KEY_FILE: &str = "shield-ca-key.pem";
+
+/// Hosts intercepted when TLS interception is on and no explicit list is given: the LLM
+/// APIs whose request bodies carry prompts.
+pub const LLM_API_HOSTS: &[&str] = &[
+    "api.openai.com",
+    "api.anthropic.com",
+    "generativelanguage.googleapis.com",
+    "api.mistral.ai",
+    "api.groq.com",
+    "api.cohere.com",
+];
+
+/// A local CA that issues a certificate per intercepted host. Clients must trust its
+/// certificate (`cert_path`) for interception to work.
+pub struct CertificateAuthority {
+    cert: Certificate,
+    cert_der: Vec<u8>,
+    cert_path: PathBuf,
+    issued: Mutex<HashMap<String, Arc<rustls::ServerConfig>>>,
+}
+
+impl CertificateAuthority {
+    /// Loads the CA from `dir`, generating and saving a new one the first time.
+    pub fn load_or_create(dir: &Path) -> Result<Self> {
+        let cert_path = dir.join(CA_CERT_FILE);
+        let key_path = dir.join(CA_KEY_FILE);
+        if !cert_path.exists() || !key_path.exists() {
+            let (cert_pem, key_pem) = Self::generate()?;
+            std::fs::create_dir_all(dir)?;
+            std::fs::write(&cert_path, cert_pem)?;
+            // A key left without its certificate is replaced, not reused.
+            let _ = std::fs::remove_file(&key_path);
+            crate::vault::write_private(&key_path, key_pem.as_bytes())?;
+        }
+
+        let cert_pem = std::fs::read_to_string(&cert_path)?;
+        let key = KeyPair::from_pem(&std::fs::read_to_string(&key_path)?)?;
+        let cert = Certificate::from_params(CertificateParams::from_ca_cert_pem(&cert_pem, key)?)?;
+        let cert_der = rustls_pemfile::certs(&mut cert_pem.as_bytes())?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No certificate in {:?}", cert_path))?;
+        Ok(Self { cert, cert_der, cert_path, issued: Mutex::new(HashMap::new()) })
+    }
+
+    fn generate() -> Result<(String, String)> {
+        let mut params = CertificateParams::new(Vec::new());
+        let mut name = DistinguishedName::new();
+        name.push(DnType::CommonName, "AXIAL Shield Local CA");
+        name.push(DnType::OrganizationName, "AXIAL");
+        params.distinguished_name = name;
+        params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+        params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign, KeyUsagePurpose::DigitalSignature];
+        let cert = Certificate::from_params(params)?;
+        Ok((cert.serialize_pem()?, cert.serialize_private_key_pem()))
+    }
+
+    /// The certificate to add to the system or client trust store.
+    pub fn cert_path(&self) -> &Path {
+        &self.cert_path
+    }
+
+    /// TLS config presenting a certificate for `host` signed by this CA. Issued once per host.
+    pub fn server_config(&self, host: &str) -> Result<Arc<rustls::ServerConfig>> {
+        if let Some(config) = self.issued.lock().unwrap().get(host) {
+            return Ok(Arc::clone(config));
+        }
+
+        let mut params = CertificateParams::new(vec![host.to_string()]);
+        if let Ok(ip) = host.parse() {
+            params.subject_alt_names = vec![SanType::IpAddress(ip)];
+        }
+        params.distinguished_name.push(DnType::CommonName, host);
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+        let leaf = Certificate::from_params(params)?;
+        let chain = vec![
+            rustls::Certificate(leaf.serialize_der_with_signer(&self.cert)?),
+            rustls::Certificate(self.cert_der.clone()),
+        ];
+
+        let mut config = rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(chain, rustls::PrivateKey(leaf.serialize_private_key_der()))?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        let config = Arc::new(config);
+        self.issued.lock().unwrap().insert(host.to_string(), Arc::clone(&config));
+        Ok(config)
+    }
+}
+
+/// Client config for the proxy's own connections to intercepted hosts, trusting the
+/// public web PKI.
+pub fn upstream_tls_config() -> rustls::ClientConfig {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+        rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(ta.subject, ta.spki, ta.name_constraints)
+    }));
+    let mut config = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_issued_certificates_chain_to_the_saved_ca() {
+        let dir = std::env::temp_dir().join(format!("axial-ca-test-{}", std::process::id()));
+        let ca = CertificateAuthority::load_or_create(&dir).unwrap();
+        let reloaded = CertificateAuthority::load_or_create(&dir).unwrap();
+        assert_eq!(ca.cert_der, reloaded.cert_der, "an existing CA is reused");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(dir.join(CA_KEY_FILE)).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(&rustls::Certificate(ca.cert_der.clone())).unwrap();
+        let client = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+        let (client_io, server_io) = tokio::io::duplex(16 * 1024);
+        let acceptor = tokio_rustls::TlsAcceptor::from(reloaded.server_config("api.openai.com").unwrap());
+        let server = tokio::spawn(async move { acceptor.accept(server_io).await.map(|_| ()) });
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(client));
+        let name = rustls::ServerName::try_from("api.openai.com").unwrap();
+        let _client = connector.connect(name, client_io).await.unwrap();
+        server.await.unwrap().unwrap();
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
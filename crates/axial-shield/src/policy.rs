@@ -0,0 +1,160 @@
+use serde::{Serialize, Deserialize};
+
+/// One allow or deny entry in a shield policy. `host` is an exact name, `*.suffix` for any
+/// subdomain of `suffix` (not `suffix` itself), or `*` for every host. Empty `ports` or
+/// `methods` match any port or method.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DomainRule {
+    pub host: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ports: Vec<u16>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub methods: Vec<String>,
+}
+
+/// What is known about an outgoing request. `None` port or method means unknown, as when
+/// only a domain is being checked.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestTarget<'a> {
+    pub host: &'a str,
+    pub port: Option<u16>,
+    pub method: Option<&'a str>,
+}
+
+impl<'a> RequestTarget<'a> {
+    pub fn host(host: &'a str) -> Self {
+        Self { host, port: None, method: None }
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim().trim_end_matches('.').to_ascii_lowercase()
+}
+
+impl DomainRule {
+    pub fn host(host: &str) -> Self {
+        Self { host: host.to_string(), ports: Vec::new(), methods: Vec::new() }
+    }
+
+    pub fn matches_host(&self, host: &str) -> bool {
+        let pattern = normalize_host(&self.host);
+        let host = normalize_host(host);
+        if pattern == "*" {
+            return true;
+        }
+        match pattern.strip_prefix("*.") {
+            Some(suffix) => host.len() > suffix.len() && host.ends_with(suffix) && host[..host.len() - suffix.len()].ends_with('.'),
+            None => host == pattern,
+        }
+    }
+
+    /// Whether the rule covers `target`. An unknown port or method is covered when
+    /// `unknown_matches` is set, so allow rules can be lenient and deny rules strict.
+    fn matches(&self, target: &RequestTarget, unknown_matches: bool) -> bool {
+        if !self.matches_host(target.host) {
+            return false;
+        }
+        let port_ok = self.ports.is_empty() || target.port.map_or(unknown_matches, |p| self.ports.contains(&p));
+        let method_ok = self.methods.is_empty()
+            || target.method.map_or(unknown_matches, |m| self.methods.iter().any(|r| r.eq_ignore_ascii_case(m)));
+        port_ok && method_ok
+    }
+
+    /// Whether the rule's host and ports cover a tunnel to `host:port`, whatever its methods.
+    fn matches_tunnel(&self, host: &str, port: u16) -> bool {
+        self.matches_host(host) && (self.ports.is_empty() || self.ports.contains(&port))
+    }
+}
+
+/// Evaluates `target` against the rules: any matching deny rule blocks it, otherwise some
+/// allow rule must match. Returns the reason when blocked.
+pub fn evaluate(allow: &[DomainRule], deny: &[DomainRule], target: &RequestTarget) -> Result<(), String> {
+    if let Some(rule) = deny.iter().find(|r| r.matches(target, false)) {
+        return Err(format!("{} is denied by rule '{}'", describe(target), rule.host));
+    }
+    if allow.iter().any(|r| r.matches(target, true)) {
+        return Ok(());
+    }
+    match allow.iter().find(|r| r.matches_host(target.host)) {
+        Some(rule) => Err(format!("{} is outside rule '{}' (ports {:?}, methods {:?})", describe(target), rule.host, rule.ports, rule.methods)),
+        None => Err(format!("Domain {} is not in the allowlist", target.host)),
+    }
+}
+
+/// Evaluates an opaque CONNECT tunnel to `host:port`. Any method can be sent through it, so
+/// a deny rule limited to some methods blocks it and an allow rule only covers it when it
+/// allows every method. Returns the reason when blocked.
+pub fn evaluate_tunnel(allow: &[DomainRule], deny: &[DomainRule], host: &str, port: u16) -> Result<(), String> {
+    if let Some(rule) = deny.iter().find(|r| r.matches_tunnel(host, port)) {
+        return Err(format!("CONNECT {}:{} is denied by rule '{}'", host, port, rule.host));
+    }
+    if allow.iter().any(|r| r.methods.is_empty() && r.matches_tunnel(host, port)) {
+        return Ok(());
+    }
+    match allow.iter().find(|r| r.matches_tunnel(host, port)) {
+        Some(rule) => Err(format!(
+            "CONNECT {}:{} can't be held to rule '{}' (methods {:?}) without interception",
+            host, port, rule.host, rule.methods
+        )),
+        None => evaluate(allow, deny, &RequestTarget { host, port: Some(port), method: None }),
+    }
+}
+
+fn describe(target: &RequestTarget) -> String {
+    let mut out = String::new();
+    if let Some(method) = target.method {
+        out.push_str(method);
+        out.push(' ');
+    }
+    out.push_str(target.host);
+    if let Some(port) = target.port {
+        out.push_str(&format!(":{}", port));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wildcards_ports_methods_and_denylist() {
+        let allow = vec![
+            DomainRule::host("*.githubusercontent.com"),
+            DomainRule { host: "api.openai.com".to_string(), ports: vec![443], methods: Vec::new() },
+            DomainRule { host: "registry.npmjs.org".to_string(), ports: Vec::new(), methods: vec!["GET".to_string()] },
+        ];
+        let deny = vec![DomainRule::host("gist.githubusercontent.com")];
+        let check = |host, port, method| evaluate(&allow, &deny, &RequestTarget { host, port, method });
+
+        assert!(check("raw.githubusercontent.com", Some(443), Some("GET")).is_ok());
+        assert!(check("githubusercontent.com", None, None).is_err(), "the bare suffix is not a subdomain");
+        assert!(check("evilgithubusercontent.com", None, None).is_err());
+        assert!(check("gist.githubusercontent.com", Some(443), None).is_err(), "deny overrides allow");
+
+        assert!(check("API.OpenAI.com.", Some(443), Some("POST")).is_ok());
+        assert!(check("api.openai.com", Some(80), None).is_err());
+        assert!(check("registry.npmjs.org", Some(443), Some("get")).is_ok());
+        assert!(check("registry.npmjs.org", Some(443), Some("PUT")).is_err());
+        assert!(check("registry.npmjs.org", None, None).is_ok(), "unknown method passes a domain-only check");
+    }
+
+    #[test]
+    fn test_tunnels_need_rules_that_allow_every_method() {
+        let allow = vec![
+            DomainRule { host: "api.openai.com".to_string(), ports: vec![443], methods: Vec::new() },
+            DomainRule { host: "registry.npmjs.org".to_string(), ports: Vec::new(), methods: vec!["GET".to_string()] },
+            DomainRule::host("*.example.com"),
+        ];
+        let deny = vec![DomainRule { host: "upload.example.com".to_string(), ports: Vec::new(), methods: vec!["PUT".to_string()] }];
+        let tunnel = |host, port| evaluate_tunnel(&allow, &deny, host, port);
+
+        assert!(tunnel("api.openai.com", 443).is_ok());
+        assert!(tunnel("api.openai.com", 8443).is_err());
+        let err = tunnel("registry.npmjs.org", 443).unwrap_err();
+        assert!(err.contains("without interception"), "{}", err);
+        assert!(tunnel("upload.example.com", 443).is_err(), "a method-limited deny rule blocks the whole tunnel");
+        assert!(tunnel("docs.example.com", 443).is_ok());
+        assert!(tunnel("unknown.org", 443).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::truth::{Severity, TruthEngine, TruthViolation, cvss_severity};
+use anyhow::{Result, anyhow};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low | Severity::Info => "note",
+    }
+}
+
+/// A SARIF 2.1.0 log with one run per engine. The engine's own severity label is kept in
+/// each result's `properties.severity` so an import gets it back unchanged.
+pub fn to_sarif(violations: &[TruthViolation]) -> Value {
+    let mut by_engine: BTreeMap<&str, Vec<&TruthViolation>> = BTreeMap::new();
+    for v in violations {
+        by_engine.entry(v.engine.as_str()).or_default().push(v);
+    }
+    let runs: Vec<Value> = by_engine.into_iter().map(|(engine, violations)| {
+        let mut rules: Vec<&str> = violations.iter().map(|v| v.rule_id.as_str()).collect();
+        rules.sort_unstable();
+        rules.dedup();
+        let results: Vec<Value> = violations.iter().map(|v| {
+            let mut location = json!({ "physicalLocation": { "artifactLocation": { "uri": v.file.replace('\\', "/") } } });
+            // SARIF lines start at 1; 0 means the finding is about the whole file.
+            if v.line > 0 {
+                location["physicalLocation"]["region"] = json!({ "startLine": v.line });
+            }
+            json!({
+                "ruleId": v.rule_id,
+                "ruleIndex": rules.binary_search(&v.rule_id.as_str()).unwrap_or_default(),
+                "level": level(v.level()),
+                "message": { "text": v.message },
+                "locations": [location],
+                "properties": { "severity": v.severity },
+            })
+        }).collect();
+        json!({
+            "tool": { "driver": {
+                "name": engine,
+                "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+            } },
+            "results": results,
+        })
+    }).collect();
+    json!({ "$schema": SARIF_SCHEMA, "version": SARIF_VERSION, "runs": runs })
+}
+
+/// Reads the results of a SARIF 2.1.0 log from any tool. Severity comes from our own
+/// `properties.severity`, else the rule's `security-severity` CVSS score, else the result's
+/// or rule's level.
+pub fn from_sarif(log: &Value) -> Result<Vec<TruthViolation>> {
+    if log["version"] != SARIF_VERSION {
+        return Err(anyhow!("Unsupported SARIF version {}; expected {}", log["version"], SARIF_VERSION));
+    }
+    let runs = log["runs"].as_array().ok_or_else(|| anyhow!("SARIF log has no runs"))?;
+    let mut violations = Vec::new();
+    for run in runs {
+        let engine = run.pointer("/tool/driver/name").and_then(|n| n.as_str()).unwrap_or("sarif");
+        let rules = run.pointer("/tool/driver/rules").and_then(|r| r.as_array()).map(Vec::as_slice).unwrap_or_default();
+        for result in run["results"].as_array().into_iter().flatten() {
+            let rule = result["ruleIndex"].as_u64().and_then(|i| rules.get(i as usize))
+                .or_else(|| rules.iter().find(|r| r["id"] == result["ruleId"]));
+            let rule_id = result["ruleId"].as_str()
+                .or_else(|| result.pointer("/rule/id")?.as_str())
+                .or_else(|| rule?["id"].as_str())
+                .unwrap_or("unknown");
+            let level = result["level"].as_str()
+                .or_else(|| rule?.pointer("/defaultConfiguration/level")?.as_str())
+                .unwrap_or("warning");
+            let security_severity = rule
+                .and_then(|r| r.pointer("/properties/security-severity"))
+                .and_then(|s| s.as_str()?.parse::<f64>().ok());
+            let severity = match (result.pointer("/properties/severity").and_then(|s| s.as_str()), security_severity) {
+                (Some(severity), _) => severity.to_string(),
+                (None, Some(score)) => cvss_severity(score).to_string(),
+                (None, None) => match level {
+                    "error" => "high",
+                    "note" => "low",
+                    "none" => "info",
+                    _ => "medium",
+                }.to_string(),
+            };
+            let location = result.pointer("/locations/0/physicalLocation");
+            let uri = location.and_then(|l| l.pointer("/artifactLocation/uri")).and_then(|u| u.as_str()).unwrap_or("");
+            violations.push(TruthViolation {
+                engine: engine.to_string(),
+                rule_id: rule_id.to_string(),
+                severity,
+                message: result.pointer("/message/text").and_then(|t| t.as_str())
+                    .or_else(|| rule?.pointer("/shortDescription/text")?.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                file: uri.strip_prefix("file://").unwrap_or(uri).to_string(),
+                line: location.and_then(|l| l.pointer("/region/startLine")).and_then(|l| l.as_u64()).unwrap_or(0) as usize,
+            });
+        }
+    }
+    Ok(violations)
+}
+
+/// Feeds SARIF reports from tools AXIAL doesn't wrap into the invariant system. `path` is a
+/// report, or a directory whose `*.sarif` files are all read.
+pub struct SarifImport;
+
+impl TruthEngine for SarifImport {
+    fn name(&self) -> &'static str { "sarif" }
+
+    fn run(&self, path: &str) -> Result<Vec<TruthViolation>> {
+        let path = Path::new(path);
+        let reports = if path.is_dir() {
+            let mut reports: Vec<_> = std::fs::read_dir(path)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "sarif"))
+                .collect();
+            reports.sort();
+            reports
+        } else {
+            vec![path.to_path_buf()]
+        };
+        let mut violations = Vec::new();
+        for report in reports {
+            let content = std::fs::read_to_string(&report)
+                .map_err(|e| anyhow!("Failed to read SARIF report {:?}: {}", report, e))?;
+            let log = serde_json::from_str(&content).map_err(|e| anyhow!("Invalid SARIF report {:?}: {}", report, e))?;
+            violations.extend(from_sarif(&log)?);
+        }
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sarif_round_trip_and_foreign_import() {
+        let violations = vec![
+            TruthViolation { engine: "semgrep".into(), rule_id: "sql-injection".into(), severity: "ERROR".into(), message: "Tainted SQL".into(), file: "src/db.py".into(), line: 12 },
+            TruthViolation { engine: "osv-scanner".into(), rule_id: "GHSA-1".into(), severity: "medium".into(), message: "time 0.1".into(), file: "Cargo.lock".into(), line: 0 },
+        ];
+        let log = to_sarif(&violations);
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(log["runs"].as_array().unwrap().len(), 2);
+        assert!(log.pointer("/runs/0/results/0/locations/0/physicalLocation/region").is_none());
+        let back = from_sarif(&log).unwrap();
+        assert_eq!(back.iter().map(|v| (v.engine.as_str(), v.severity.as_str(), v.line)).collect::<Vec<_>>(),
+            [("osv-scanner", "medium", 0), ("semgrep", "ERROR", 12)]);
+
+        let codeql = json!({"version": "2.1.0", "runs": [{
+            "tool": {"driver": {"name": "CodeQL", "rules": [{"id": "js/xss", "properties": {"security-severity": "7.8"}}]}},
+            "results": [{"ruleId": "js/xss", "ruleIndex": 0, "message": {"text": "Cross-site scripting"},
+                "locations": [{"physicalLocation": {"artifactLocation": {"uri": "web/app.js"}, "region": {"startLine": 40}}}]},
+                {"ruleId": "js/unused", "level": "note", "message": {"text": "Unused variable"}}]
+        }]});
+        let imported = from_sarif(&codeql).unwrap();
+        assert_eq!((imported[0].severity.as_str(), imported[0].file.as_str(), imported[0].line), ("high", "web/app.js", 40));
+        assert_eq!(imported[1].level(), Severity::Low);
+        assert!(from_sarif(&json!({"version": "2.0.0", "runs": []})).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+use anyhow::{Result, anyhow};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use regex::Regex;
+use serde::{Serialize, Deserialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Leading bytes of a vault file, followed by the nonce and the ciphertext.
+const MAGIC: &[u8; 4] = b"AXV1";
+const NONCE_LEN: usize = 12;
+
+/// Longest token `StreamRestorer` holds back while waiting for its closing `>>`.
+const MAX_TOKEN_LEN: usize = 64;
+
+fn token_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"<<[A-Z0-9_]+_\d+>>").expect("vault token pattern"))
+}
+
+/// Token prefix for a detector id or configured label: `email` becomes `EMAIL`, `us_ssn` becomes `SSN`.
+pub fn token_label(label: &str) -> String {
+    match label {
+        "us_ssn" => "SSN".to_string(),
+        "credit_card" => "CARD".to_string(),
+        "configured_pattern" => "PII".to_string(),
+        other => other.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+            .collect(),
+    }
+}
+
+/// 256-bit key for vault files.
+/// Creates `path` readable by the owner only and writes `contents`. The mode is set as the
+/// file is created, so the secret is never readable by others, even briefly. Fails if the
+/// file already exists.
+pub(crate) fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)?;
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct VaultKey([u8; 32]);
+
+impl VaultKey {
+    /// Reads the key file, creating it with a random key (readable by the owner only) if
+    /// it doesn't exist.
+    pub fn load_or_create(path: &Path) -> Result<Self> {
+        if path.exists() {
+            let bytes = std::fs::read(path)?;
+            let key: [u8; 32] = bytes.try_into().map_err(|_| anyhow!("Vault key {:?} must be 32 bytes", path))?;
+            return Ok(Self(key));
+        }
+        let key: [u8; 32] = ChaCha20Poly1305::generate_key(&mut OsRng).into();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        write_private(path, &key)?;
+        Ok(Self(key))
+    }
+
+    /// Parses a key given as 64 hex characters, e.g. from an environment variable.
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let hex = hex.trim();
+        if hex.len() != 64 {
+            return Err(anyhow!("Vault key must be 64 hex characters"));
+        }
+        let mut key = [0u8; 32];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)?;
+        }
+        Ok(Self(key))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct VaultData {
+    /// Token (without the angle brackets) to original value.
+    tokens: BTreeMap<String, String>,
+    counters: BTreeMap<String, usize>,
+}
+
+/// Maps placeholder tokens such as `<<EMAIL_1>>` to the values they replaced. The same value
+/// always gets the same token, so a model sees consistent placeholders across a run. Stored
+/// encrypted; the values never leave the machine.
+pub struct Vault {
+    path: Option<PathBuf>,
+    key: VaultKey,
+    data: VaultData,
+    by_value: HashMap<(String, String), String>,
+}
+
+impl Vault {
+    /// Opens the vault at `path`, starting empty if the file doesn't exist yet.
+    pub fn open(path: &Path, key: VaultKey) -> Result<Self> {
+        let data = if path.exists() {
+            Self::decrypt(&std::fs::read(path)?, &key)
+                .map_err(|e| anyhow!("Failed to open vault {:?}: {}", path, e))?
+        } else {
+            VaultData::default()
+        };
+        let mut vault = Self { path: Some(path.to_path_buf()), key, data, by_value: HashMap::new() };
+        vault.index();
+        Ok(vault)
+    }
+
+    /// A vault that is never written to disk.
+    pub fn in_memory() -> Self {
+        let key = VaultKey(ChaCha20Poly1305::generate_key(&mut OsRng).into());
+        Self { path: None, key, data: VaultData::default(), by_value: HashMap::new() }
+    }
+
+    fn index(&mut self) {
+        self.by_value = self.data.tokens.iter()
+            .filter_map(|(token, value)| {
+                let (label, _) = token.rsplit_once('_')?;
+                Some(((label.to_string(), value.clone()), token.clone()))
+            })
+            .collect();
+    }
+
+    fn decrypt(bytes: &[u8], key: &VaultKey) -> Result<VaultData> {
+        if bytes.len() < MAGIC.len() + NONCE_LEN || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(anyhow!("not a vault file"));
+        }
+        let (nonce, ciphertext) = bytes[MAGIC.len()..].split_at(NONCE_LEN);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&key.0));
+        let plaintext = cipher.decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("wrong key or corrupted vault"))?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// Encrypts the vault to its file, replacing the previous contents atomically.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&self.key.0));
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher.encrypt(&nonce, serde_json::to_vec(&self.data)?.as_slice())
+            .map_err(|_| anyhow!("Failed to encrypt vault"))?;
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&nonce);
+        bytes.extend_from_slice(&ciphertext);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, bytes)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.tokens.is_empty()
+    }
+
+    /// The token standing in for `value`, allocating the next number for `label` if new.
+    pub fn token_for(&mut self, label: &str, value: &str) -> String {
+        let key = (label.to_string(), value.to_string());
+        let token = match self.by_value.get(&key) {
+            Some(token) => token.clone(),
+            None => {
+                let counter = self.data.counters.entry(label.to_string()).or_insert(0);
+                *counter += 1;
+                let token = format!("{}_{}", label, counter);
+                self.data.tokens.insert(token.clone(), value.to_string());
+                self.by_value.insert(key, token.clone());
+                token
+            }
+        };
+        format!("<<{}>>", token)
+    }
+
+    /// Replaces every known token in `text` with its original value. Unknown tokens are left as-is.
+    pub fn restore(&self, text: &str) -> String {
+        token_pattern().replace_all(text, |caps: &regex::Captures| {
+            let token = &caps[0];
+            self.data.tokens.get(&token[2..token.len() - 2]).cloned().unwrap_or_else(|| token.to_string())
+        }).into_owned()
+    }
+
+    /// Restores tokens in every string inside a JSON value, such as tool call arguments.
+    pub fn restore_value(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::String(s) => *s = self.restore(s),
+            serde_json::Value::Array(items) => items.iter_mut().for_each(|v| self.restore_value(v)),
+            serde_json::Value::Object(map) => map.values_mut().for_each(|v| self.restore_value(v)),
+            _ => {}
+        }
+    }
+}
+
+/// Restores tokens in streamed text, holding back a possible token split across chunks.
+#[derive(Debug, Default)]
+pub struct StreamRestorer {
+    pending: String,
+}
+
+impl StreamRestorer {
+    /// Returns the text that is safe to emit after appending `chunk`.
+    pub fn push(&mut self, chunk: &str, vault: &Vault) -> String {
+        self.pending.push_str(chunk);
+        let split = match self.pending.rfind("<<") {
+            Some(start) if !self.pending[start..].contains(">>") && self.pending.len() - start < MAX_TOKEN_LEN => start,
+            _ if self.pending.ends_with('<') => self.pending.len() - 1,
+            _ => self.pending.len(),
+        };
+        let ready: String = self.pending.drain(..split).collect();
+        vault.restore(&ready)
+    }
+
+    /// Flushes whatever is still held back at the end of the stream.
+    pub fn finish(&mut self, vault: &Vault) -> String {
+        vault.restore(&std::mem::take(&mut self.pending))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_are_stable_encrypted_and_restored_across_chunks() {
+        let path = std::env::temp_dir().join(format!("axial-vault-test-{}.vault", std::process::id()));
+        let key = VaultKey::from_hex(&"ab".repeat(32)).unwrap();
+        let mut vault = Vault::open(&path, key.clone()).unwrap();
+        assert_eq!(vault.token_for("EMAIL", "jane@example.com"), "<<EMAIL_1>>");
+        assert_eq!(vault.token_for("EMAIL", "bob@example.com"), "<<EMAIL_2>>");
+        assert_eq!(vault.token_for("EMAIL", "jane@example.com"), "<<EMAIL_1>>");
+        vault.save().unwrap();
+        assert!(!String::from_utf8_lossy(&std::fs::read(&path).unwrap()).contains("jane@example.com"));
+
+        let vault = Vault::open(&path, key).unwrap();
+        assert_eq!(vault.restore("Reply to <<EMAIL_1>>, not <<EMAIL_9>>"), "Reply to jane@example.com, not <<EMAIL_9>>");
+        assert!(Vault::open(&path, VaultKey::from_hex(&"cd".repeat(32)).unwrap()).is_err());
+
+        let mut restorer = StreamRestorer::default();
+        let mut out = String::new();
+        for chunk in ["Hi <", "<EMA", "IL_2>", "> and <<EMAIL_1>>!"] {
+            out.push_str(&restorer.push(chunk, &vault));
+        }
+        out.push_str(&restorer.finish(&vault));
+        assert_eq!(out, "Hi bob@example.com and jane@example.com!");
+        let _ = std::fs::remove_file(&path);
+
+        let key_path = path.with_extension("key");
+        let _ = std::fs::remove_file(&key_path);
+        let created = VaultKey::load_or_create(&key_path).unwrap();
+        assert_eq!(VaultKey::load_or_create(&key_path).unwrap().0, created.0);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&key_path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        let _ = std::fs::remove_file(&key_path);
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::{Result, anyhow};
+use std::path::{Component, Path, PathBuf};
+
+/// Zones inside every root that tools may read but never write.
+pub const DEFAULT_READ_ONLY: &[&str] = &[".git", ".axial"];
+
+/// Symlinks followed while resolving one path before giving up on a loop.
+const MAX_SYMLINKS: usize = 40;
+
+/// Decides where agent tools may write. A path is resolved before it is checked: symlinks
+/// are followed for the parts that exist and `..` is applied lexically for the rest, so a
+/// file that doesn't exist yet can't escape through `../` or a symlinked parent.
+#[derive(Debug, Clone)]
+pub struct WorkspaceGuard {
+    roots: Vec<PathBuf>,
+    /// Relative zones apply inside every root; absolute ones as given.
+    read_only: Vec<PathBuf>,
+}
+
+impl WorkspaceGuard {
+    /// A guard allowing writes under `root` only, with no read-only zones.
+    pub fn new(root: &Path) -> Result<Self> {
+        Ok(Self { roots: vec![resolve(root, &std::env::current_dir()?)?], read_only: Vec::new() })
+    }
+
+    /// A guard for a project checkout: `root` with `DEFAULT_READ_ONLY` protected.
+    pub fn for_workspace(root: &Path) -> Result<Self> {
+        let mut guard = Self::new(root)?;
+        guard.read_only = DEFAULT_READ_ONLY.iter().map(PathBuf::from).collect();
+        Ok(guard)
+    }
+
+    pub fn with_root(mut self, root: &Path) -> Result<Self> {
+        self.roots.push(resolve(root, &self.roots[0])?);
+        Ok(self)
+    }
+
+    pub fn with_read_only(mut self, zone: &Path) -> Self {
+        self.read_only.push(zone.to_path_buf());
+        self
+    }
+
+    /// The first root; relative paths are taken relative to it.
+    pub fn root(&self) -> &Path {
+        &self.roots[0]
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Every read-only zone, resolved, once per root for the relative ones.
+    pub fn read_only_zones(&self) -> Result<Vec<PathBuf>> {
+        let mut zones = Vec::new();
+        for root in &self.roots {
+            for zone in &self.read_only {
+                zones.push(if zone.is_absolute() { resolve(zone, root)? } else { resolve(&root.join(zone), root)? });
+            }
+        }
+        zones.dedup();
+        Ok(zones)
+    }
+
+    /// Where a write to `path` would really land.
+    pub fn resolve(&self, path: &Path) -> Result<PathBuf> {
+        resolve(path, self.root())
+    }
+
+    /// Resolves `path` and checks it is inside a root and outside every read-only zone.
+    /// Returns the resolved path, which is the one to write to.
+    pub fn check_write(&self, path: &Path) -> Result<PathBuf> {
+        let resolved = self.resolve(path)?;
+        let root = self.roots.iter().find(|root| resolved.starts_with(root)).ok_or_else(|| {
+            anyhow!("Security Violation: Write to {:?} resolves to {:?}, outside the workspace", path, resolved)
+        })?;
+        for zone in &self.read_only {
+            let zone = if zone.is_absolute() { resolve(zone, root)? } else { resolve(&root.join(zone), root)? };
+            if resolved.starts_with(&zone) {
+                return Err(anyhow!("Security Violation: Write to {:?} is inside read-only {:?}", path, zone));
+            }
+        }
+        Ok(resolved)
+    }
+}
+
+/// Makes `path` absolute against `base`, follows symlinks for every component that exists
+/// and normalizes `.` and `..` for the rest.
+fn resolve(path: &Path, base: &Path) -> Result<PathBuf> {
+    // Components still to walk, last first, so a symlink's target can be spliced in.
+    let mut pending: Vec<PathBuf> = components(path);
+    let mut resolved = if path.is_absolute() { PathBuf::new() } else { base.to_path_buf() };
+    let mut links = 0;
+    while let Some(next) = pending.pop() {
+        match next.components().next() {
+            Some(Component::Prefix(_)) | Some(Component::RootDir) => resolved = next,
+            Some(Component::ParentDir) => {
+                resolved.pop();
+            }
+            Some(Component::Normal(_)) => {
+                let candidate = resolved.join(&next);
+                match std::fs::symlink_metadata(&candidate) {
+                    Ok(metadata) if metadata.file_type().is_symlink() => {
+                        links += 1;
+                        if links > MAX_SYMLINKS {
+                            return Err(anyhow!("Too many levels of symlinks resolving {:?}", path));
+                        }
+                        pending.extend(components(&std::fs::read_link(&candidate)?));
+                    }
+                    _ => resolved = candidate,
+                }
+            }
+            Some(Component::CurDir) | None => {}
+        }
+    }
+    Ok(resolved)
+}
+
+/// `path` split into single-component paths, in reverse order. A prefix and root directory
+/// stay together so an absolute path restarts from its root.
+fn components(path: &Path) -> Vec<PathBuf> {
+    let has_prefix = matches!(path.components().next(), Some(Component::Prefix(_)));
+    let mut parts: Vec<PathBuf> = Vec::new();
+    for component in path.components() {
+        match parts.last_mut() {
+            Some(prefix) if component == Component::RootDir && has_prefix => prefix.push(component.as_os_str()),
+            _ => parts.push(PathBuf::from(component.as_os_str())),
+        }
+    }
+    parts.reverse();
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_resolve_traversal_and_symlinks_before_checking() {
+        let base = std::env::temp_dir().join(format!("axial-workspace-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let (root, other, outside) = (base.join("repo"), base.join("scratch"), base.join("outside"));
+        for dir in [root.join(".git"), root.join("src"), other.clone(), outside.clone()] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        let guard = WorkspaceGuard::for_workspace(&root).unwrap().with_root(&other).unwrap();
+
+        assert!(guard.check_write(Path::new("src/new/../main.rs")).unwrap().ends_with("repo/src/main.rs"));
+        assert!(guard.check_write(&other.join("notes.md")).is_ok());
+        assert!(guard.check_write(Path::new("../../etc/passwd")).is_err());
+        assert!(guard.check_write(Path::new("src/missing/../../../outside/x")).is_err());
+        assert!(guard.check_write(Path::new(".git/config")).is_err());
+        assert!(guard.check_write(Path::new("src/../.axial/state.json")).is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&outside, root.join("src/escape")).unwrap();
+            std::os::unix::fs::symlink("../.git", root.join("src/meta")).unwrap();
+            assert!(guard.check_write(Path::new("src/escape/not-yet/created.txt")).is_err());
+            assert!(guard.check_write(Path::new("src/meta/hooks/pre-commit")).is_err());
+            assert!(guard.check_write(Path::new("src/escape/../src/lib.rs")).is_err());
+        }
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}